@@ -84,6 +84,13 @@ where
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
     C::Api: mmr_rpc::MmrRuntimeApi<Block, <Block as sp_runtime::traits::Block>::Hash, BlockNumber>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+    C::Api: pallet_private_transactions_rpc::ConfidentialTransactionsRuntimeApi<
+            Block,
+            AccountId,
+            Balance,
+        >,
+    C::Api: pallet_bridge_rpc::BridgeRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+    C::Api: pallet_launch_claim_rpc::LaunchClaimRuntimeApi<Block, AccountId, Balance, BlockNumber>,
     C::Api: BabeApi<Block>,
     C::Api: BlockBuilder<Block>,
     P: TransactionPool + 'static,
@@ -91,6 +98,11 @@ where
     B: sc_client_api::Backend<Block> + Send + Sync + 'static,
     B::State: sc_client_api::backend::StateBackend<sp_runtime::traits::HashingFor<Block>>, {
     use mmr_rpc::{Mmr, MmrApiServer};
+    use pallet_bridge_rpc::{Bridge, BridgeApiServer};
+    use pallet_launch_claim_rpc::{LaunchClaim, LaunchClaimApiServer};
+    use pallet_private_transactions_rpc::{
+        ConfidentialTransactions, ConfidentialTransactionsApiServer,
+    };
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
     use sc_consensus_babe_rpc::{Babe, BabeApiServer};
     use sc_consensus_grandpa_rpc::{Grandpa, GrandpaApiServer};
@@ -123,6 +135,9 @@ where
         .into_rpc(),
     )?;
     io.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+    io.merge(ConfidentialTransactions::new(client.clone()).into_rpc())?;
+    io.merge(Bridge::new(client.clone()).into_rpc())?;
+    io.merge(LaunchClaim::new(client.clone()).into_rpc())?;
     io.merge(
         Babe::new(client.clone(), babe_worker_handle.clone(), keystore, select_chain).into_rpc(),
     )?;
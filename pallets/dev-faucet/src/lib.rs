@@ -0,0 +1,71 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! # Dev Faucet Pallet
+//!
+//! Mints funds to any account so local and test chains can be funded without sudo balance
+//! setting. The runtime only includes it when built with the `dev-faucet` feature, which
+//! refuses to compile together with `on-chain-release-build`.
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use frame_support::{
+        pallet_prelude::*,
+        traits::{Currency, Imbalance},
+    };
+    use frame_system::pallet_prelude::*;
+    use sp_runtime::traits::Zero;
+
+    type BalanceOf<T> =
+        <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// The currency minted by `dev_fund`.
+        type Currency: Currency<Self::AccountId>;
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// `amount` was minted into `who`.
+        Funded { who: T::AccountId, amount: BalanceOf<T> },
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// Nothing was minted, e.g. because `amount` is below the existential deposit.
+        NothingMinted,
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Mint `amount` into `who`. Any signed account may call this.
+        #[pallet::call_index(0)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+        pub fn dev_fund(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+            amount: BalanceOf<T>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+
+            let minted = T::Currency::deposit_creating(&who, amount);
+            ensure!(!minted.peek().is_zero(), Error::<T>::NothingMinted);
+
+            Self::deposit_event(Event::Funded { who, amount });
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{Error, Event, mock::*};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::traits::BadOrigin;
+
+#[test]
+fn dev_fund_mints_into_any_account() {
+    new_test_ext().execute_with(|| {
+        let issuance = Balances::total_issuance();
+
+        assert_ok!(DevFaucet::dev_fund(RuntimeOrigin::signed(1), 2, 500));
+
+        assert_eq!(Balances::free_balance(2), 500);
+        assert_eq!(Balances::total_issuance(), issuance + 500);
+        System::assert_last_event(RuntimeEvent::DevFaucet(Event::Funded { who: 2, amount: 500 }));
+    });
+}
+
+#[test]
+fn dev_fund_requires_a_signed_origin_and_a_mintable_amount() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(DevFaucet::dev_fund(RuntimeOrigin::none(), 2, 500), BadOrigin);
+        assert_noop!(
+            DevFaucet::dev_fund(RuntimeOrigin::signed(1), 2, ExistentialDeposit::get() - 1),
+            Error::<Test>::NothingMinted
+        );
+    });
+}
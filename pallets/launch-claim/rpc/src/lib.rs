@@ -0,0 +1,105 @@
+//! RPC interface for the launch-claim pallet.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::error::{ErrorObject, ErrorObjectOwned},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+
+pub use pallet_launch_claim_runtime_api::LaunchClaimApi as LaunchClaimRuntimeApi;
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i32 = 1;
+
+#[rpc(client, server)]
+pub trait LaunchClaimApi<BlockHash, AccountId, Balance, BlockNumber> {
+    /// Returns `(total, claimed, claimable_now)` for `who`, or `null` if `who` has no claim.
+    #[method(name = "launchClaim_progress")]
+    fn claim_progress(
+        &self,
+        who: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Option<(Balance, Balance, Balance)>>;
+
+    /// Returns `who`'s most recent claims as `(block, amount)`, oldest first.
+    #[method(name = "launchClaim_claimLog")]
+    fn claim_log(
+        &self,
+        who: AccountId,
+        at: Option<BlockHash>,
+    ) -> RpcResult<Vec<(BlockNumber, Balance)>>;
+
+    /// Returns the next block at which more of `who`'s claim unlocks, or `null` if `who` has no
+    /// claim or it is fully vested.
+    #[method(name = "launchClaim_nextUnlock")]
+    fn next_unlock(&self, who: AccountId, at: Option<BlockHash>) -> RpcResult<Option<BlockNumber>>;
+}
+
+/// Provides RPC methods to query the launch-claim pallet.
+pub struct LaunchClaim<C, Block> {
+    client: Arc<C>,
+    _marker: PhantomData<Block>,
+}
+
+impl<C, Block> LaunchClaim<C, Block> {
+    /// Creates a new instance of the launch-claim RPC helper.
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client, _marker: Default::default() }
+    }
+}
+
+impl<C, Block, AccountId, Balance, BlockNumber>
+    LaunchClaimApiServer<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber>
+    for LaunchClaim<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: LaunchClaimRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+    AccountId: Codec + Send + Sync + 'static,
+    Balance: Codec + Send + Sync + 'static,
+    BlockNumber: Codec + Send + Sync + 'static,
+{
+    fn claim_progress(
+        &self,
+        who: AccountId,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Option<(Balance, Balance, Balance)>> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.claim_progress(at, who).map_err(runtime_error_into_rpc_err)
+    }
+
+    fn claim_log(
+        &self,
+        who: AccountId,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Vec<(BlockNumber, Balance)>> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.claim_log(at, who).map_err(runtime_error_into_rpc_err)
+    }
+
+    fn next_unlock(
+        &self,
+        who: AccountId,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<Option<BlockNumber>> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.next_unlock(at, who).map_err(runtime_error_into_rpc_err)
+    }
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> ErrorObjectOwned {
+    ErrorObject::owned(RUNTIME_ERROR, "Runtime error", Some(format!("{err:?}")))
+}
@@ -0,0 +1,28 @@
+//! Runtime API definition for the launch-claim pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::Codec;
+
+sp_api::decl_runtime_apis! {
+    /// Read-only queries against the launch-claim pallet.
+    pub trait LaunchClaimApi<AccountId, Balance, BlockNumber>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+    {
+        /// Returns `(total, claimed, claimable_now)` for `who`, or `None` if `who` has no claim.
+        fn claim_progress(who: AccountId) -> Option<(Balance, Balance, Balance)>;
+
+        /// Returns `who`'s most recent claims as `(block, amount)`, oldest first.
+        fn claim_log(who: AccountId) -> Vec<(BlockNumber, Balance)>;
+
+        /// Returns the next block at which more of `who`'s claim unlocks, or `None` if `who` has
+        /// no claim or it is fully vested.
+        fn next_unlock(who: AccountId) -> Option<BlockNumber>;
+    }
+}
@@ -7,21 +7,31 @@ mod tests;
 
 pub use pallet::*;
 
+/// Read access to the amount an account can claim right now, for other pallets to build on.
+pub trait ClaimableBalance<AccountId, Balance> {
+    /// Amount `who` could claim at the current block, with vesting applied.
+    fn claimable(who: &AccountId) -> Balance;
+}
+
 #[frame_support::pallet]
 pub mod pallet {
     use frame_support::{
         pallet_prelude::*,
-        traits::{Currency, ExistenceRequirement},
+        traits::{Currency, ExistenceRequirement, OnUnbalanced, WithdrawReasons},
     };
     use frame_system::pallet_prelude::*;
     use sp_runtime::{
-        ArithmeticError, SaturatedConversion, Saturating, traits::UniqueSaturatedInto,
+        ArithmeticError, SaturatedConversion, Saturating,
+        traits::{CheckedSub, UniqueSaturatedInto},
     };
     use sp_std::prelude::*;
 
     // Define the Balance type from the Currency trait
-    type BalanceOf<T> =
+    pub type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    type NegativeImbalanceOf<T> = <<T as Config>::Currency as Currency<
+        <T as frame_system::Config>::AccountId,
+    >>::NegativeImbalance;
 
     /// The current storage version.
     const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
@@ -41,6 +51,28 @@ pub mod pallet {
 
         #[pallet::constant]
         type VestingPeriod: Get<BlockNumberFor<Self>>;
+
+        /// Blocks after a claim's start before its vesting half starts unlocking; the upfront half
+        /// is claimable from the start. Vesting then runs linearly over the rest of
+        /// `VestingPeriod`.
+        #[pallet::constant]
+        type VestingCliff: Get<BlockNumberFor<Self>>;
+
+        /// Flat fee deducted from every `claim`/`claim_full` payout. Zero disables it.
+        #[pallet::constant]
+        type ClaimFee: Get<BalanceOf<Self>>;
+
+        /// Where the claim fee goes; `()` burns it.
+        type ClaimFeeDestination: OnUnbalanced<NegativeImbalanceOf<Self>>;
+
+        /// Whether the owner may `deactivate` claims and activate them again later. When false,
+        /// `activate` is a one-time latch.
+        #[pallet::constant]
+        type AllowDeactivation: Get<bool>;
+
+        /// Entries kept per account in `ClaimLog`; the oldest is evicted once it is full.
+        #[pallet::constant]
+        type MaxClaimLogLen: Get<u32>;
     }
 
     /// The origin that is allowed to perform administrative actions.
@@ -53,6 +85,11 @@ pub mod pallet {
     #[pallet::getter(fn is_activated)]
     pub type Activated<T: Config> = StorageValue<_, bool, ValueQuery>;
 
+    /// Block from which claims open without a call to `activate`. Cleared once activated.
+    #[pallet::storage]
+    #[pallet::getter(fn activation_block)]
+    pub type ActivationBlock<T: Config> = StorageValue<_, BlockNumberFor<T>, OptionQuery>;
+
     #[derive(
         Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen, Default,
     )]
@@ -73,6 +110,30 @@ pub mod pallet {
         ValueQuery,
     >;
 
+    /// Last idempotency key accepted from `claim_full` for each account.
+    #[pallet::storage]
+    #[pallet::getter(fn last_claim_key)]
+    pub type LastClaimKey<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u64, OptionQuery>;
+
+    /// `(block, amount)` of each account's most recent claims, oldest first, as an audit trail
+    /// that outlives events. Holds at most `MaxClaimLogLen` entries.
+    #[pallet::storage]
+    #[pallet::getter(fn claim_log)]
+    pub type ClaimLog<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<(BlockNumberFor<T>, BalanceOf<T>), T::MaxClaimLogLen>,
+        ValueQuery,
+    >;
+
+    /// External purchase ids already credited by `add_claim`.
+    #[pallet::storage]
+    #[pallet::getter(fn purchase_processed)]
+    pub type ProcessedPurchases<T: Config> =
+        StorageMap<_, Blake2_128Concat, [u8; 32], (), OptionQuery>;
+
     /// Storage map to maintain the set of authorized relayers.
     #[pallet::storage]
     #[pallet::getter(fn relayers)]
@@ -83,18 +144,25 @@ pub mod pallet {
     pub enum Event<T: Config> {
         /// The claims process has been activated.
         ClaimsActivated,
+        /// The claims process has been suspended until activated again.
+        ClaimsDeactivated,
         /// A new claim has been added for an account. [who, total_amount]
         ClaimAdded { who: T::AccountId, total_amount: BalanceOf<T>, rate: u128 },
         /// A claim has been veto for an account. [who, total_amount]
         VetoClaimChange { who: T::AccountId, total_amount: BalanceOf<T> },
-        /// An account has successfully claimed their tokens. [who, amount]
-        Claimed { who: T::AccountId, amount: BalanceOf<T> },
+        /// An account has successfully claimed their tokens and received `amount - fee`.
+        /// [who, amount, fee]
+        Claimed { who: T::AccountId, amount: BalanceOf<T>, fee: BalanceOf<T> },
         /// A new relayer has been added. [who]
         RelayerAdded { who: T::AccountId },
         /// A relayer has been removed. [who]
         RelayerRemoved { who: T::AccountId },
         /// Exchange Rate Updated
         ExchangeRateUpdated(u128),
+        /// Claims were scheduled to open at `block`, or the schedule was cleared.
+        ActivationBlockSet { block: Option<BlockNumberFor<T>> },
+        /// A claim and its progress were moved to another account. [from, to]
+        ClaimReassigned { from: T::AccountId, to: T::AccountId },
     }
 
     #[pallet::error]
@@ -111,12 +179,26 @@ pub mod pallet {
         NoSuchRelayer,
         /// A claim for the specified account already exists.
         InsufficientLaunchpadBalance,
-        /// The user is trying to claim more than their available balance.
+        /// The user is trying to claim nothing, or more than their available balance.
         InsufficientClaim,
         /// Not Owner
         NotOwner,
         /// No Owner,
         NoOwner,
+        /// The idempotency key was already used for the caller's previous claim.
+        DuplicateClaim,
+        /// The claimed amount does not exceed the claim fee.
+        ClaimBelowFee,
+        /// The external purchase id was already credited.
+        DuplicatePurchase,
+        /// Claims are activated but no funding source account was configured.
+        FundingSourceNotSet,
+        /// `AllowDeactivation` is off, so activation cannot be undone.
+        DeactivationDisabled,
+        /// The account has no claim to pay out or reassign.
+        NoClaim,
+        /// The account to reassign to already has a claim.
+        ClaimExists,
     }
 
     /// Storage for the funding account ---
@@ -156,13 +238,15 @@ pub mod pallet {
     }
     #[pallet::call]
     impl<T: Config> Pallet<T> {
-        /// Activate the claims process. Can only be called once by the owner.
+        /// Activate the claims process (only owner). Unless `AllowDeactivation` is set, this can
+        /// only happen once.
         #[pallet::call_index(0)]
         #[pallet::weight(T::DbWeight::get().writes(1))]
         pub fn activate(origin: OriginFor<T>) -> DispatchResult {
             Self::ensure_owner(origin)?;
             ensure!(!Self::is_activated(), Error::<T>::AlreadyActivated);
 
+            ActivationBlock::<T>::kill();
             Activated::<T>::put(true);
             Self::deposit_event(Event::ClaimsActivated);
             Ok(())
@@ -199,15 +283,23 @@ pub mod pallet {
         }
 
         /// Add a claim for a specific account. Can only be called by an authorized relayer.
+        ///
+        /// `external_id` identifies the purchase in the relayer's source system; each id is
+        /// credited once, so a retried import is rejected instead of double-crediting.
         #[pallet::call_index(3)]
-        #[pallet::weight(T::DbWeight::get().writes(1))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 2))]
         pub fn add_claim(
             origin: OriginFor<T>,
             who: T::AccountId,
             usdt_amount: u128,
+            external_id: [u8; 32],
         ) -> DispatchResult {
             let relayer = ensure_signed(origin)?;
             ensure!(Self::relayers(&relayer).is_some(), Error::<T>::NotRelayer);
+            ensure!(
+                !ProcessedPurchases::<T>::contains_key(external_id),
+                Error::<T>::DuplicatePurchase
+            );
 
             let rate = ExchangeRate::<T>::get();
             ensure!(rate > 0, "Exchange rate not set");
@@ -218,6 +310,7 @@ pub mod pallet {
 
             let tokens = usdt_normalized.checked_mul(rate).ok_or(ArithmeticError::Underflow)?;
             let now = <frame_system::Pallet<T>>::block_number();
+            ProcessedPurchases::<T>::insert(external_id, ());
 
             Claims::<T>::try_mutate(who.clone(), |current| {
                 current.total += tokens.unique_saturated_into();
@@ -228,95 +321,32 @@ pub mod pallet {
         }
 
         /// Claim the full amount available for the caller.
+        ///
+        /// `idempotency_key` must differ from the key of the caller's previous `claim_full`, so
+        /// a resubmitted transaction is rejected instead of paying out twice.
         #[pallet::call_index(4)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(3, 1))]
-        pub fn claim_full(origin: OriginFor<T>) -> DispatchResult {
+        #[pallet::weight(T::DbWeight::get().reads_writes(6, 6))]
+        pub fn claim_full(origin: OriginFor<T>, idempotency_key: u64) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            ensure!(Self::is_activated(), Error::<T>::NotActivated);
-            let now = <frame_system::Pallet<T>>::block_number();
-
-            Claims::<T>::try_mutate(who.clone(), |claim_info| -> DispatchResult {
-                let half = claim_info.total / 2u32.saturated_into();
-
-                // vested portion
-                let vesting_duration: BlockNumberFor<T> = T::VestingPeriod::get();
-                let elapsed = now.saturating_sub(claim_info.start);
-                let elapsed: BalanceOf<T> =
-                    elapsed.saturated_into::<u128>().unique_saturated_into();
-                let vesting_duration: BalanceOf<T> =
-                    vesting_duration.saturated_into::<u128>().unique_saturated_into();
-                let vested = half.min(half * elapsed / vesting_duration);
-
-                let unlocked = half + vested;
-                let claimable_amount = unlocked.saturating_sub(claim_info.claimed);
-
-                ensure!(claimable_amount > Zero::zero(), Error::<T>::InsufficientClaim);
-
-                // Transfer funds from the pallet's account to the claimant.
-                let source_account = Self::funding_source().ok_or(Error::<T>::NotActivated)?;
-
-                ensure!(
-                    T::Currency::free_balance(&source_account) > claimable_amount,
-                    Error::<T>::InsufficientLaunchpadBalance
-                );
-                T::Currency::transfer(
-                    &source_account,
-                    &who,
-                    claimable_amount,
-                    ExistenceRequirement::KeepAlive,
-                )?;
-
-                claim_info.claimed += claimable_amount;
-                Self::deposit_event(Event::Claimed { who, amount: claimable_amount });
+            Self::ensure_active()?;
+            ensure!(
+                Self::last_claim_key(&who) != Some(idempotency_key),
+                Error::<T>::DuplicateClaim
+            );
 
-                Ok(())
-            })
+            Self::do_claim(&who, None)?;
+            LastClaimKey::<T>::insert(&who, idempotency_key);
+            Ok(())
         }
 
-        /// Claim a specific amount.
+        /// Claim a specific, non-zero amount.
         #[pallet::call_index(5)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(3, 5))]
         pub fn claim(origin: OriginFor<T>, amount_to_claim: BalanceOf<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            ensure!(Self::is_activated(), Error::<T>::NotActivated);
-            let now = <frame_system::Pallet<T>>::block_number();
-
-            // Mutate the claim in storage.
-            Claims::<T>::try_mutate(&who, |claim_info| -> DispatchResult {
-                let half = claim_info.total / 2u32.into();
+            Self::ensure_active()?;
 
-                // calculate vested portion
-                let vesting_duration: BlockNumberFor<T> = T::VestingPeriod::get(); // 6 months worth of blocks
-                let elapsed = now.saturating_sub(claim_info.start);
-                let elapsed: BalanceOf<T> =
-                    elapsed.saturated_into::<u128>().unique_saturated_into();
-                let vesting_duration: BalanceOf<T> =
-                    vesting_duration.saturated_into::<u128>().unique_saturated_into();
-                let vested = half.min(half * elapsed / vesting_duration);
-
-                // total unlocked = 50% upfront + vested
-                let unlocked = half + vested;
-
-                let claimable = unlocked.saturating_sub(claim_info.claimed);
-                ensure!(amount_to_claim <= claimable, Error::<T>::InsufficientClaim);
-
-                // Transfer funds from the source account.
-                let source_account = Self::funding_source().ok_or(Error::<T>::NotActivated)?;
-                ensure!(
-                    T::Currency::free_balance(&source_account) > amount_to_claim,
-                    Error::<T>::InsufficientLaunchpadBalance
-                );
-                T::Currency::transfer(
-                    &source_account,
-                    &who,
-                    amount_to_claim,
-                    ExistenceRequirement::KeepAlive,
-                )?;
-
-                claim_info.claimed += amount_to_claim;
-                Self::deposit_event(Event::Claimed { who: who.clone(), amount: amount_to_claim });
-                Ok(())
-            })
+            Self::do_claim(&who, Some(amount_to_claim))
         }
 
         /// Veto Remove Claim (only owner)
@@ -344,6 +374,62 @@ pub mod pallet {
             Self::deposit_event(Event::ExchangeRateUpdated(new_rate));
             Ok(())
         }
+
+        /// Suspend an active claims process (only owner), blocking `claim` and `claim_full`
+        /// until it is activated again. Requires `AllowDeactivation`.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn deactivate(origin: OriginFor<T>) -> DispatchResult {
+            Self::ensure_owner(origin)?;
+            ensure!(T::AllowDeactivation::get(), Error::<T>::DeactivationDisabled);
+            ensure!(Self::is_activated(), Error::<T>::NotActivated);
+
+            Activated::<T>::put(false);
+            Self::deposit_event(Event::ClaimsDeactivated);
+            Ok(())
+        }
+
+        /// Move `from`'s claim to `to` (only owner), e.g. after `from` lost its key. Progress
+        /// already claimed, the last idempotency key and the claim log move with it.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 6))]
+        pub fn reassign_claim(
+            origin: OriginFor<T>,
+            from: T::AccountId,
+            to: T::AccountId,
+        ) -> DispatchResult {
+            Self::ensure_owner(origin)?;
+            ensure!(Claims::<T>::contains_key(&from), Error::<T>::NoClaim);
+            ensure!(!Claims::<T>::contains_key(&to), Error::<T>::ClaimExists);
+
+            Claims::<T>::insert(&to, Claims::<T>::take(&from));
+            if let Some(key) = LastClaimKey::<T>::take(&from) {
+                LastClaimKey::<T>::insert(&to, key);
+            }
+            let log = ClaimLog::<T>::take(&from);
+            if !log.is_empty() {
+                ClaimLog::<T>::insert(&to, log);
+            }
+
+            Self::deposit_event(Event::ClaimReassigned { from, to });
+            Ok(())
+        }
+
+        /// Schedule claims to open at `block` without a call to `activate` (only owner), or
+        /// clear the schedule with `None`.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_activation_block(
+            origin: OriginFor<T>,
+            block: Option<BlockNumberFor<T>>,
+        ) -> DispatchResult {
+            Self::ensure_owner(origin)?;
+            ensure!(!Self::is_activated(), Error::<T>::AlreadyActivated);
+
+            ActivationBlock::<T>::set(block);
+            Self::deposit_event(Event::ActivationBlockSet { block });
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
@@ -353,5 +439,182 @@ pub mod pallet {
             ensure!(who == owner, Error::<T>::NotOwner);
             Ok(who)
         }
+
+        /// Fails with `NotActivated` unless claims are open. Reaching the scheduled
+        /// `ActivationBlock` activates claims, as `activate` would.
+        fn ensure_active() -> DispatchResult {
+            if Self::is_activated() {
+                return Ok(());
+            }
+            let now = frame_system::Pallet::<T>::block_number();
+            match ActivationBlock::<T>::get() {
+                Some(block) if now >= block => {
+                    ActivationBlock::<T>::kill();
+                    Activated::<T>::put(true);
+                    Self::deposit_event(Event::ClaimsActivated);
+                    Ok(())
+                },
+                _ => Err(Error::<T>::NotActivated.into()),
+            }
+        }
+
+        /// `(total, claimed, claimable_now)` for `who` at the current block, or `None` if `who`
+        /// has no claim.
+        pub fn claim_progress(
+            who: &T::AccountId,
+        ) -> Option<(BalanceOf<T>, BalanceOf<T>, BalanceOf<T>)> {
+            let info = Claims::<T>::try_get(who).ok()?;
+            let now = <frame_system::Pallet<T>>::block_number();
+            Some((info.total, info.claimed, Self::claimable_now(&info, now)))
+        }
+
+        /// First block after the current one at which more of `who`'s claim unlocks: the next
+        /// step of the linear vesting, or the cliff if the vesting half unlocks there all at once.
+        /// `None` if `who` has no claim or it is fully vested.
+        pub fn next_unlock(who: &T::AccountId) -> Option<BlockNumberFor<T>> {
+            let info = Claims::<T>::try_get(who).ok()?;
+            if info.total.is_zero() {
+                return None;
+            }
+            let now = <frame_system::Pallet<T>>::block_number();
+            let cliff = T::VestingCliff::get();
+            let vesting_start = info.start.saturating_add(cliff);
+            let vesting_half: u128 = (info.total / 2u32.saturated_into()).saturated_into();
+            let duration: u128 = T::VestingPeriod::get().saturating_sub(cliff).saturated_into();
+            if vesting_half != 0 && duration == 0 && now < vesting_start {
+                return Some(vesting_start);
+            }
+
+            // Before the cliff nothing has vested, the same as at the cliff itself.
+            let elapsed: u128 = now.saturating_sub(vesting_start).saturated_into();
+            if vesting_half == 0 || elapsed >= duration {
+                return None;
+            }
+
+            // Smallest `elapsed` at which `vesting_half * elapsed / duration` (see
+            // `claimable_now`) steps past its current value.
+            let vested = vesting_half.saturating_mul(elapsed) / duration;
+            let next = (vested + 1).saturating_mul(duration).div_ceil(vesting_half);
+            Some(vesting_start.saturating_add(next.saturated_into()))
+        }
+
+        /// Amount of `info` that can be claimed at `now`: 50% upfront from `start`, plus the
+        /// other half vesting linearly from `start + VestingCliff` until `start + VestingPeriod`,
+        /// minus what was already claimed.
+        ///
+        /// The upfront half rounds up and the vested half rounds down, and what is unlocked is
+        /// always derived from `total` rather than accumulated per claim, so rounding never
+        /// compounds: once the period is over exactly `total` has been unlocked.
+        pub fn claimable_now(
+            info: &ClaimInfo<BalanceOf<T>, BlockNumberFor<T>>,
+            now: BlockNumberFor<T>,
+        ) -> BalanceOf<T> {
+            let vesting_half = info.total / 2u32.saturated_into();
+            let upfront = info.total.saturating_sub(vesting_half);
+
+            let cliff = T::VestingCliff::get();
+            let elapsed = now.saturating_sub(info.start);
+            let vesting_duration = T::VestingPeriod::get().saturating_sub(cliff);
+            let vested = if elapsed < cliff {
+                Zero::zero()
+            } else if vesting_duration.is_zero() {
+                vesting_half
+            } else {
+                let elapsed: BalanceOf<T> =
+                    (elapsed - cliff).saturated_into::<u128>().unique_saturated_into();
+                let vesting_duration: BalanceOf<T> =
+                    vesting_duration.saturated_into::<u128>().unique_saturated_into();
+                vesting_half.min(vesting_half.saturating_mul(elapsed) / vesting_duration)
+            };
+
+            // total unlocked = 50% upfront + vested
+            upfront.saturating_add(vested).saturating_sub(info.claimed)
+        }
+
+        /// Pays out `requested`, or everything claimable if `None`, to `who`. `claim` and
+        /// `claim_full` both go through this single `try_mutate` of `who`'s `ClaimInfo`, which
+        /// only ever grows `claimed` and never removes the entry, so interleaved partial and full
+        /// claims always see the same accounting.
+        fn do_claim(who: &T::AccountId, requested: Option<BalanceOf<T>>) -> DispatchResult {
+            let source_account = Self::funding_source().ok_or(Error::<T>::FundingSourceNotSet)?;
+            // `Claims` is a value query: mutating an absent entry would store a default one.
+            ensure!(Claims::<T>::contains_key(who), Error::<T>::NoClaim);
+            let now = <frame_system::Pallet<T>>::block_number();
+
+            Claims::<T>::try_mutate(who, |claim_info| -> DispatchResult {
+                let claimable = Self::claimable_now(claim_info, now);
+                let amount = match requested {
+                    Some(amount) => {
+                        ensure!(amount > Zero::zero(), Error::<T>::InsufficientClaim);
+                        ensure!(amount <= claimable, Error::<T>::InsufficientClaim);
+                        amount
+                    },
+                    None => {
+                        ensure!(claimable > Zero::zero(), Error::<T>::InsufficientClaim);
+                        claimable
+                    },
+                };
+
+                // Transfer funds from the source account, which must keep at least the existential
+                // deposit after paying out `amount` (fee included).
+                ensure!(
+                    T::Currency::free_balance(&source_account)
+                        .checked_sub(&amount)
+                        .is_some_and(|left| left >= T::Currency::minimum_balance()),
+                    Error::<T>::InsufficientLaunchpadBalance
+                );
+                let fee = Self::pay_out(&source_account, who, amount)?;
+
+                claim_info.claimed += amount;
+                Self::log_claim(who, now, amount);
+                Self::deposit_event(Event::Claimed { who: who.clone(), amount, fee });
+                Ok(())
+            })
+        }
+
+        /// Pays `amount - ClaimFee` from `source` to `who` and hands the fee to
+        /// `ClaimFeeDestination`. Returns the fee taken.
+        fn pay_out(
+            source: &T::AccountId,
+            who: &T::AccountId,
+            amount: BalanceOf<T>,
+        ) -> Result<BalanceOf<T>, DispatchError> {
+            let fee = T::ClaimFee::get();
+            ensure!(fee.is_zero() || amount > fee, Error::<T>::ClaimBelowFee);
+
+            T::Currency::transfer(
+                source,
+                who,
+                amount.saturating_sub(fee),
+                ExistenceRequirement::KeepAlive,
+            )?;
+            if !fee.is_zero() {
+                let imbalance = T::Currency::withdraw(
+                    source,
+                    fee,
+                    WithdrawReasons::FEE,
+                    ExistenceRequirement::KeepAlive,
+                )?;
+                T::ClaimFeeDestination::on_unbalanced(imbalance);
+            }
+            Ok(fee)
+        }
+
+        /// Appends a claim to `who`'s `ClaimLog`, evicting the oldest entry if it is full.
+        fn log_claim(who: &T::AccountId, now: BlockNumberFor<T>, amount: BalanceOf<T>) {
+            ClaimLog::<T>::mutate(who, |log| {
+                if log.is_full() && !log.is_empty() {
+                    log.remove(0);
+                }
+                let _ = log.try_push((now, amount));
+            });
+        }
+    }
+}
+
+impl<T: Config> ClaimableBalance<T::AccountId, BalanceOf<T>> for Pallet<T> {
+    fn claimable(who: &T::AccountId) -> BalanceOf<T> {
+        let now = frame_system::Pallet::<T>::block_number();
+        Self::claimable_now(&Claims::<T>::get(who), now)
     }
 }
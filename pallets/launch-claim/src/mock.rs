@@ -1,4 +1,7 @@
-use frame_support::{PalletId, assert_ok, derive_impl, pallet_prelude::ConstU32, parameter_types};
+use frame_support::{
+    PalletId, assert_ok, derive_impl, pallet_prelude::ConstU32, parameter_types,
+    traits::tokens::imbalance::ResolveTo,
+};
 use sp_runtime::BuildStorage;
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -42,7 +45,7 @@ impl frame_system::Config for Test {
 }
 
 parameter_types! {
-    pub const ExistentialDeposit: u128 = 1;
+    pub static ExistentialDeposit: u128 = 1;
     pub const MaxLocks: u32 = 50;
     pub const MaxReserves: u32 = 50;
 }
@@ -73,12 +76,22 @@ parameter_types! {
     pub const RelayerThreshold: u32 = 0; // require 0 signature for mock
     pub const MaxSignatures: u32 = 10;   // max 10 signatures per release
     pub const VestingPeriod: u64 = 60 * 60 * 24 * 30 * 6; // ~6 months worth of blocks
+    pub static VestingCliff: u64 = 0;
+    pub static ClaimFee: u128 = 0;
+    pub const FeeCollector: AccountId = 99;
+    pub static AllowDeactivation: bool = true;
+    pub const MaxClaimLogLen: u32 = 3;
 }
 
 impl crate::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type Currency = Balances;
     type VestingPeriod = VestingPeriod;
+    type VestingCliff = VestingCliff;
+    type ClaimFee = ClaimFee;
+    type ClaimFeeDestination = ResolveTo<FeeCollector, Balances>;
+    type AllowDeactivation = AllowDeactivation;
+    type MaxClaimLogLen = MaxClaimLogLen;
 }
 
 pub const XOR: u128 = 10_u128.pow(18);
@@ -1,5 +1,6 @@
 use crate::mock::{
-    Balances, LaunchClaim, RuntimeOrigin, System, Test, VestingPeriod, XOR, new_test_ext,
+    AllowDeactivation, Balances, ClaimFee, ExistentialDeposit, FeeCollector, LaunchClaim,
+    RuntimeEvent, RuntimeOrigin, System, Test, VestingCliff, VestingPeriod, XOR, new_test_ext,
 };
 use frame_support::{assert_noop, assert_ok};
 
@@ -8,22 +9,22 @@ const USDT: u128 = 1_000_000;
 fn add_claim_works() {
     new_test_ext().execute_with(|| {
         // Relayer adds claim for user 1
-        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 1, USDT));
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 1, USDT, [1; 32]));
 
         // User 1 should now have 20 claimable tokens
         assert_eq!(LaunchClaim::claims(1).total, 20 * XOR);
 
-        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 1, 10 * USDT));
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 1, 10 * USDT, [2; 32]));
 
         // User 1 should now have 220 claimable tokens
         assert_eq!(LaunchClaim::claims(1).total, 220 * XOR);
 
         // 0.1 USDT
-        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 8, USDT / 10));
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 8, USDT / 10, [3; 32]));
         // User 8 should now have 20 claimable tokens
         assert_eq!(LaunchClaim::claims(8).total, 2 * XOR);
         // 0.01 USDT
-        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 18, USDT / 100));
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 18, USDT / 100, [4; 32]));
         // User 18 should now have 20 claimable tokens
         assert_eq!(LaunchClaim::claims(18).total, 2 * XOR / 10);
     });
@@ -32,12 +33,12 @@ fn add_claim_works() {
 #[test]
 fn claim_full_works() {
     new_test_ext().execute_with(|| {
-        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT));
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [5; 32]));
 
         // User claims full
         assert_eq!(LaunchClaim::claims(31).total, 2_000 * XOR);
         assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
-        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31)));
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1));
         // only half at a time
         assert_eq!(Balances::free_balance(31), 1_000 * XOR);
         // source account balance reduced
@@ -53,29 +54,29 @@ fn claim_full_works() {
 #[test]
 fn claim_full_with_vesting_progress() {
     new_test_ext().execute_with(|| {
-        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT));
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [6; 32]));
 
         // User claims full
         assert_eq!(LaunchClaim::claims(31).total, 2_000 * XOR);
         assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
-        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31)));
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1));
         // only half at a time
         assert_eq!(Balances::free_balance(31), 1_000 * XOR);
 
         // Fast forward halfway through vesting
         System::set_block_number(VestingPeriod::get() / 2 + 1);
-        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31)));
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 2));
         // Should get: half already claimed + half of the remaining half = 75%
         // account for fees
         assert_eq!(Balances::free_balance(31), 1_500 * XOR);
         assert_eq!(LaunchClaim::claims(31).claimed, 1_500 * XOR);
         // Advance to end of vesting
         System::set_block_number(VestingPeriod::get() + 1);
-        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31)));
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 3));
         assert_eq!(Balances::free_balance(31), 2_000 * XOR);
         assert_eq!(LaunchClaim::claims(31).claimed, 2_000 * XOR);
         assert_noop!(
-            LaunchClaim::claim_full(RuntimeOrigin::signed(31)),
+            LaunchClaim::claim_full(RuntimeOrigin::signed(31), 4),
             crate::Error::<Test>::InsufficientClaim
         );
         assert_noop!(
@@ -88,7 +89,7 @@ fn claim_full_with_vesting_progress() {
 #[test]
 fn claim_partial_works() {
     new_test_ext().execute_with(|| {
-        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 50 * USDT));
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 50 * USDT, [7; 32]));
 
         // User claims full
         assert_eq!(LaunchClaim::claims(31).total, 1_000 * XOR);
@@ -108,7 +109,7 @@ fn claim_partial_works() {
 #[test]
 fn cannot_claim_when_inactive() {
     new_test_ext().execute_with(|| {
-        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 50 * USDT));
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 50 * USDT, [8; 32]));
         assert_noop!(
             LaunchClaim::claim(RuntimeOrigin::signed(31), 1_000 * XOR),
             crate::Error::<Test>::NotActivated
@@ -119,12 +120,526 @@ fn cannot_claim_when_inactive() {
 #[test]
 fn cannot_claim_more_than_available() {
     new_test_ext().execute_with(|| {
-        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 5 * USDT));
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 5 * USDT, [9; 32]));
         assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
         // User tries to claim more than stored
         assert_noop!(
-            LaunchClaim::claim(RuntimeOrigin::signed(10), 2_000 * XOR),
+            LaunchClaim::claim(RuntimeOrigin::signed(31), 2_000 * XOR),
             crate::Error::<Test>::InsufficientClaim
         );
     })
 }
+
+#[test]
+fn accounts_without_a_claim_cannot_claim_or_block_a_reassignment() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 5 * USDT, [26; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+
+        // A zero claim would otherwise pass the amount check and store an empty entry.
+        assert_noop!(
+            LaunchClaim::claim(RuntimeOrigin::signed(32), 0),
+            crate::Error::<Test>::NoClaim
+        );
+        assert_noop!(
+            LaunchClaim::claim_full(RuntimeOrigin::signed(32), 1),
+            crate::Error::<Test>::NoClaim
+        );
+        assert!(!crate::Claims::<Test>::contains_key(32));
+        assert!(LaunchClaim::claim_log(32).is_empty());
+
+        assert_ok!(LaunchClaim::reassign_claim(RuntimeOrigin::signed(1), 31, 32));
+        assert_eq!(LaunchClaim::claims(32).total, 100 * XOR);
+    });
+}
+
+#[test]
+fn claim_full_rejects_repeated_idempotency_key() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [10; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 7));
+        assert_eq!(Balances::free_balance(31), 1_000 * XOR);
+
+        // Halfway through vesting there is something to claim, but the resubmitted key is
+        // rejected without paying out.
+        System::set_block_number(VestingPeriod::get() / 2 + 1);
+        assert_noop!(
+            LaunchClaim::claim_full(RuntimeOrigin::signed(31), 7),
+            crate::Error::<Test>::DuplicateClaim
+        );
+        assert_eq!(Balances::free_balance(31), 1_000 * XOR);
+
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 8));
+        assert_eq!(Balances::free_balance(31), 1_500 * XOR);
+        assert_eq!(LaunchClaim::last_claim_key(31), Some(8));
+    });
+}
+
+#[test]
+fn claim_fee_is_deducted_and_sent_to_destination() {
+    new_test_ext().execute_with(|| {
+        ClaimFee::set(10 * XOR);
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [11; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+
+        assert_ok!(LaunchClaim::claim(RuntimeOrigin::signed(31), 500 * XOR));
+        assert_eq!(Balances::free_balance(31), 490 * XOR);
+        assert_eq!(Balances::free_balance(FeeCollector::get()), 10 * XOR);
+        assert_eq!(Balances::free_balance(1), 9_500 * XOR);
+        // The fee counts towards what was claimed.
+        assert_eq!(LaunchClaim::claims(31).claimed, 500 * XOR);
+        System::assert_last_event(RuntimeEvent::LaunchClaim(crate::Event::Claimed {
+            who: 31,
+            amount: 500 * XOR,
+            fee: 10 * XOR,
+        }));
+
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1));
+        assert_eq!(Balances::free_balance(31), 980 * XOR);
+        assert_eq!(Balances::free_balance(FeeCollector::get()), 20 * XOR);
+
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 32, USDT, [12; 32]));
+        assert_noop!(
+            LaunchClaim::claim(RuntimeOrigin::signed(32), 10 * XOR),
+            crate::Error::<Test>::ClaimBelowFee
+        );
+    });
+}
+
+#[test]
+fn only_the_upfront_half_is_claimable_before_the_cliff() {
+    new_test_ext().execute_with(|| {
+        let cliff = VestingPeriod::get() / 4;
+        VestingCliff::set(cliff);
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [13; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+        // The claim starts at block 1, with the upfront half claimable straight away.
+        let start = 1;
+        assert_eq!(LaunchClaim::claimable_now(&LaunchClaim::claims(31), start), 1_000 * XOR);
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1));
+        assert_eq!(Balances::free_balance(31), 1_000 * XOR);
+
+        // The vesting half waits for the cliff, and has not vested anything at the cliff itself.
+        for now in [start + cliff - 1, start + cliff] {
+            System::set_block_number(now);
+            assert_eq!(LaunchClaim::claimable_now(&LaunchClaim::claims(31), now), 0);
+            assert_noop!(
+                LaunchClaim::claim_full(RuntimeOrigin::signed(31), 2),
+                crate::Error::<Test>::InsufficientClaim
+            );
+        }
+
+        // Halfway through the post-cliff period, half of the vesting half has unlocked.
+        let post_cliff = VestingPeriod::get() - cliff;
+        System::set_block_number(start + cliff + post_cliff / 2);
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 2));
+        assert_eq!(Balances::free_balance(31), 1_500 * XOR);
+
+        System::set_block_number(start + VestingPeriod::get());
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 3));
+        assert_eq!(Balances::free_balance(31), 2_000 * XOR);
+    });
+}
+
+#[test]
+fn claim_progress_tracks_partial_claims() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(LaunchClaim::claim_progress(&31), None);
+
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [14; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+        assert_eq!(LaunchClaim::claim_progress(&31), Some((2_000 * XOR, 0, 1_000 * XOR)));
+
+        assert_ok!(LaunchClaim::claim(RuntimeOrigin::signed(31), 400 * XOR));
+        assert_eq!(LaunchClaim::claim_progress(&31), Some((2_000 * XOR, 400 * XOR, 600 * XOR)));
+
+        System::set_block_number(VestingPeriod::get() / 2 + 1);
+        assert_eq!(LaunchClaim::claim_progress(&31), Some((2_000 * XOR, 400 * XOR, 1_100 * XOR)));
+
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1));
+        assert_eq!(LaunchClaim::claim_progress(&31), Some((2_000 * XOR, 1_500 * XOR, 0)));
+
+        System::set_block_number(VestingPeriod::get() + 1);
+        assert_eq!(LaunchClaim::claim_progress(&31), Some((2_000 * XOR, 1_500 * XOR, 500 * XOR)));
+    });
+}
+
+#[test]
+fn add_claim_rejects_a_repeated_external_id() {
+    new_test_ext().execute_with(|| {
+        let purchase = [0xAB; 32];
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 10 * USDT, purchase));
+        assert!(LaunchClaim::purchase_processed(purchase).is_some());
+
+        // A retried import of the same purchase, even for another account, is not credited.
+        assert_noop!(
+            LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 10 * USDT, purchase),
+            crate::Error::<Test>::DuplicatePurchase
+        );
+        assert_noop!(
+            LaunchClaim::add_claim(RuntimeOrigin::signed(10), 32, 10 * USDT, purchase),
+            crate::Error::<Test>::DuplicatePurchase
+        );
+        assert_eq!(LaunchClaim::claims(31).total, 200 * XOR);
+
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 10 * USDT, [0xAC; 32]));
+        assert_eq!(LaunchClaim::claims(31).total, 400 * XOR);
+    });
+}
+
+#[test]
+fn claims_fail_without_funding_source() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 50 * USDT, [15; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+        crate::FundingSource::<Test>::kill();
+
+        assert_noop!(
+            LaunchClaim::claim(RuntimeOrigin::signed(31), XOR),
+            crate::Error::<Test>::FundingSourceNotSet
+        );
+        assert_noop!(
+            LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1),
+            crate::Error::<Test>::FundingSourceNotSet
+        );
+    });
+}
+
+#[test]
+fn claims_can_be_deactivated_and_reactivated() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 50 * USDT, [16; 32]));
+        assert_noop!(
+            LaunchClaim::deactivate(RuntimeOrigin::signed(1)),
+            crate::Error::<Test>::NotActivated
+        );
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+        assert_noop!(
+            LaunchClaim::deactivate(RuntimeOrigin::signed(31)),
+            crate::Error::<Test>::NotOwner
+        );
+
+        assert_ok!(LaunchClaim::deactivate(RuntimeOrigin::signed(1)));
+        System::assert_last_event(RuntimeEvent::LaunchClaim(crate::Event::ClaimsDeactivated));
+        assert!(!LaunchClaim::is_activated());
+        assert_noop!(
+            LaunchClaim::claim(RuntimeOrigin::signed(31), XOR),
+            crate::Error::<Test>::NotActivated
+        );
+        assert_noop!(
+            LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1),
+            crate::Error::<Test>::NotActivated
+        );
+
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+        assert_ok!(LaunchClaim::claim(RuntimeOrigin::signed(31), XOR));
+    });
+}
+
+#[test]
+fn activation_is_one_time_when_deactivation_is_disabled() {
+    new_test_ext().execute_with(|| {
+        AllowDeactivation::set(false);
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+        assert_noop!(
+            LaunchClaim::deactivate(RuntimeOrigin::signed(1)),
+            crate::Error::<Test>::DeactivationDisabled
+        );
+        assert_noop!(
+            LaunchClaim::activate(RuntimeOrigin::signed(1)),
+            crate::Error::<Test>::AlreadyActivated
+        );
+    });
+}
+
+#[test]
+fn odd_totals_vest_to_exactly_the_total() {
+    new_test_ext().execute_with(|| {
+        let total = 1_001;
+        crate::Claims::<Test>::insert(31, crate::ClaimInfo { total, claimed: 0, start: 1 });
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+
+        // The odd unit goes to the upfront half.
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1));
+        assert_eq!(Balances::free_balance(31), 501);
+
+        let period = VestingPeriod::get();
+        for (key, block) in [(2, 1 + period / 3), (3, 1 + period / 2 + 7), (4, period)] {
+            System::set_block_number(block);
+            assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), key));
+            assert!(Balances::free_balance(31) < total);
+        }
+
+        System::set_block_number(1 + period);
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 5));
+        assert_eq!(Balances::free_balance(31), total);
+        assert_eq!(LaunchClaim::claims(31).claimed, total);
+        assert_eq!(LaunchClaim::claim_progress(&31), Some((total, total, 0)));
+    });
+}
+
+#[test]
+fn claimable_balance_matches_what_claim_full_pays() {
+    use crate::ClaimableBalance;
+
+    new_test_ext().execute_with(|| {
+        assert_eq!(<LaunchClaim as ClaimableBalance<_, _>>::claimable(&31), 0);
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [17; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+        System::set_block_number(1 + VestingPeriod::get() / 4);
+
+        let claimable = <LaunchClaim as ClaimableBalance<_, _>>::claimable(&31);
+        assert!(claimable > 0);
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1));
+        assert_eq!(Balances::free_balance(31), claimable);
+        assert_eq!(<LaunchClaim as ClaimableBalance<_, _>>::claimable(&31), 0);
+    });
+}
+
+#[test]
+fn each_claim_is_appended_to_the_claim_log() {
+    new_test_ext().execute_with(|| {
+        assert!(LaunchClaim::claim_log(31).is_empty());
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [18; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+
+        assert_ok!(LaunchClaim::claim(RuntimeOrigin::signed(31), 100 * XOR));
+        System::set_block_number(5);
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1));
+
+        let full = LaunchClaim::claim_progress(&31).unwrap().1 - 100 * XOR;
+        assert_eq!(LaunchClaim::claim_log(31).into_inner(), vec![(1, 100 * XOR), (5, full)]);
+    });
+}
+
+#[test]
+fn zero_claims_are_rejected_without_logging() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [28; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+
+        assert_noop!(
+            LaunchClaim::claim(RuntimeOrigin::signed(31), 0),
+            crate::Error::<Test>::InsufficientClaim
+        );
+        assert!(LaunchClaim::claim_log(31).is_empty());
+        assert_eq!(LaunchClaim::claims(31).claimed, 0);
+    });
+}
+
+#[test]
+fn claim_log_evicts_the_oldest_entry_past_the_bound() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [19; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+
+        for block in 1..=4u64 {
+            System::set_block_number(block);
+            assert_ok!(LaunchClaim::claim(RuntimeOrigin::signed(31), block as u128 * XOR));
+        }
+
+        // MaxClaimLogLen is 3 in the mock, so the claim at block 1 was dropped.
+        assert_eq!(
+            LaunchClaim::claim_log(31).into_inner(),
+            vec![(2, 2 * XOR), (3, 3 * XOR), (4, 4 * XOR)]
+        );
+    });
+}
+
+#[test]
+fn next_unlock_follows_the_vesting_schedule() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(LaunchClaim::next_unlock(&31), None);
+
+        let cliff = VestingPeriod::get() / 4;
+        VestingCliff::set(cliff);
+        // A vesting half of 5 steps up once every fifth of the post-cliff period.
+        crate::Claims::<Test>::insert(31, crate::ClaimInfo { total: 10, claimed: 0, start: 1 });
+        let vesting_start = 1 + cliff;
+        let step = (VestingPeriod::get() - cliff) / 5;
+
+        // The upfront half is already unlocked, so the first unlock is the first vesting step.
+        assert_eq!(LaunchClaim::next_unlock(&31), Some(vesting_start + step));
+
+        System::set_block_number(vesting_start);
+        assert_eq!(LaunchClaim::next_unlock(&31), Some(vesting_start + step));
+
+        System::set_block_number(vesting_start + step - 1);
+        assert_eq!(LaunchClaim::next_unlock(&31), Some(vesting_start + step));
+        let info = LaunchClaim::claims(31);
+        assert!(
+            LaunchClaim::claimable_now(&info, vesting_start + step) >
+                LaunchClaim::claimable_now(&info, vesting_start + step - 1)
+        );
+
+        System::set_block_number(vesting_start + step);
+        assert_eq!(LaunchClaim::next_unlock(&31), Some(vesting_start + 2 * step));
+
+        System::set_block_number(1 + VestingPeriod::get());
+        assert_eq!(LaunchClaim::next_unlock(&31), None);
+    });
+}
+
+#[test]
+fn interleaved_partial_and_full_claims_keep_consistent_accounting() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [20; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+
+        // Same block: a partial claim, a full claim of the rest, then nothing is left.
+        assert_ok!(LaunchClaim::claim(RuntimeOrigin::signed(31), 300 * XOR));
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1));
+        assert_noop!(
+            LaunchClaim::claim(RuntimeOrigin::signed(31), 1),
+            crate::Error::<Test>::InsufficientClaim
+        );
+        assert_eq!(LaunchClaim::claims(31).claimed, 1_000 * XOR);
+
+        // The full claim leaves the entry in place for later partial claims to build on.
+        System::set_block_number(1 + VestingPeriod::get() / 2);
+        assert_ok!(LaunchClaim::claim(RuntimeOrigin::signed(31), 100 * XOR));
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 2));
+
+        let info = LaunchClaim::claims(31);
+        assert_eq!(info.total, 2_000 * XOR);
+        assert_eq!(info.claimed, 1_500 * XOR);
+        assert_eq!(Balances::free_balance(31), info.claimed);
+        assert_eq!(Balances::free_balance(1), 10_000 * XOR - info.claimed);
+    });
+}
+
+#[test]
+fn reassign_claim_moves_the_claim_and_its_progress() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [22; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+        assert_ok!(LaunchClaim::claim(RuntimeOrigin::signed(31), 400 * XOR));
+        let before = LaunchClaim::claim_progress(&31);
+
+        assert_noop!(
+            LaunchClaim::reassign_claim(RuntimeOrigin::signed(10), 31, 32),
+            crate::Error::<Test>::NotOwner
+        );
+        assert_ok!(LaunchClaim::reassign_claim(RuntimeOrigin::signed(1), 31, 32));
+        System::assert_last_event(RuntimeEvent::LaunchClaim(crate::Event::ClaimReassigned {
+            from: 31,
+            to: 32,
+        }));
+
+        assert_eq!(LaunchClaim::claim_progress(&31), None);
+        assert_eq!(LaunchClaim::claim_progress(&32), before);
+        assert!(LaunchClaim::claim_log(31).is_empty());
+        assert_eq!(LaunchClaim::claim_log(32).into_inner(), vec![(1, 400 * XOR)]);
+
+        // The new account continues from the existing progress.
+        assert_noop!(
+            LaunchClaim::claim(RuntimeOrigin::signed(32), 601 * XOR),
+            crate::Error::<Test>::InsufficientClaim
+        );
+        assert_ok!(LaunchClaim::claim(RuntimeOrigin::signed(32), 600 * XOR));
+    });
+}
+
+#[test]
+fn reassign_claim_rejects_collisions_and_missing_claims() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [23; 32]));
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 32, 10 * USDT, [24; 32]));
+
+        assert_noop!(
+            LaunchClaim::reassign_claim(RuntimeOrigin::signed(1), 31, 32),
+            crate::Error::<Test>::ClaimExists
+        );
+        assert_noop!(
+            LaunchClaim::reassign_claim(RuntimeOrigin::signed(1), 33, 34),
+            crate::Error::<Test>::NoClaim
+        );
+        assert_eq!(LaunchClaim::claims(31).total, 2_000 * XOR);
+        assert_eq!(LaunchClaim::claims(32).total, 200 * XOR);
+    });
+}
+
+#[test]
+fn claims_open_at_the_scheduled_activation_block() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [25; 32]));
+        assert_noop!(
+            LaunchClaim::set_activation_block(RuntimeOrigin::signed(31), Some(10)),
+            crate::Error::<Test>::NotOwner
+        );
+        assert_ok!(LaunchClaim::set_activation_block(RuntimeOrigin::signed(1), Some(10)));
+        System::assert_last_event(RuntimeEvent::LaunchClaim(crate::Event::ActivationBlockSet {
+            block: Some(10),
+        }));
+
+        System::set_block_number(9);
+        assert_noop!(
+            LaunchClaim::claim(RuntimeOrigin::signed(31), XOR),
+            crate::Error::<Test>::NotActivated
+        );
+        assert_noop!(
+            LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1),
+            crate::Error::<Test>::NotActivated
+        );
+
+        System::set_block_number(10);
+        assert_ok!(LaunchClaim::claim(RuntimeOrigin::signed(31), XOR));
+        System::assert_has_event(RuntimeEvent::LaunchClaim(crate::Event::ClaimsActivated));
+        assert!(LaunchClaim::is_activated());
+        assert_eq!(LaunchClaim::activation_block(), None);
+
+        // Only the first claim after the scheduled block activates.
+        System::reset_events();
+        assert_ok!(LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1));
+        assert!(
+            !System::events()
+                .iter()
+                .any(|r| r.event == RuntimeEvent::LaunchClaim(crate::Event::ClaimsActivated))
+        );
+        assert_noop!(
+            LaunchClaim::set_activation_block(RuntimeOrigin::signed(1), Some(20)),
+            crate::Error::<Test>::AlreadyActivated
+        );
+    });
+}
+
+#[test]
+fn cleared_activation_block_keeps_claims_closed() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [26; 32]));
+        assert_ok!(LaunchClaim::set_activation_block(RuntimeOrigin::signed(1), Some(5)));
+        assert_ok!(LaunchClaim::set_activation_block(RuntimeOrigin::signed(1), None));
+
+        System::set_block_number(5);
+        assert_noop!(
+            LaunchClaim::claim(RuntimeOrigin::signed(31), XOR),
+            crate::Error::<Test>::NotActivated
+        );
+        assert!(!LaunchClaim::is_activated());
+    });
+}
+
+#[test]
+fn claims_cannot_take_the_funding_source_below_existential_deposit() {
+    new_test_ext().execute_with(|| {
+        ExistentialDeposit::set(XOR);
+        assert_ok!(LaunchClaim::add_claim(RuntimeOrigin::signed(10), 31, 100 * USDT, [27; 32]));
+        assert_ok!(LaunchClaim::activate(RuntimeOrigin::signed(1)));
+
+        // Leave the funding source with 500 XOR above the existential deposit.
+        assert_ok!(Balances::force_set_balance(RuntimeOrigin::root(), 1, 501 * XOR));
+
+        assert_noop!(
+            LaunchClaim::claim(RuntimeOrigin::signed(31), 500 * XOR + 1),
+            crate::Error::<Test>::InsufficientLaunchpadBalance
+        );
+        assert_ok!(LaunchClaim::claim(RuntimeOrigin::signed(31), 500 * XOR));
+        assert_eq!(Balances::free_balance(1), XOR);
+
+        assert_noop!(
+            LaunchClaim::claim_full(RuntimeOrigin::signed(31), 1),
+            crate::Error::<Test>::InsufficientLaunchpadBalance
+        );
+    });
+}
@@ -0,0 +1,146 @@
+//! RPC interface for the bridge pallet.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::error::{ErrorObject, ErrorObjectOwned},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H160, H256};
+use sp_runtime::traits::Block as BlockT;
+
+pub use pallet_bridge_runtime_api::{BridgeApi as BridgeRuntimeApi, ReleaseCheck};
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i32 = 1;
+/// Error code returned when the supplied amount cannot be encoded into a message id.
+const INVALID_AMOUNT: i32 = 2;
+
+#[rpc(client, server)]
+pub trait BridgeApi<BlockHash, AccountId, Balance, BlockNumber> {
+    /// Returns the message id a `lock` with these arguments would emit.
+    #[method(name = "bridge_computeMessageId")]
+    fn compute_message_id(
+        &self,
+        sender: AccountId,
+        amount: Balance,
+        eth_recipient: H160,
+        nonce: u64,
+        at: Option<BlockHash>,
+    ) -> RpcResult<H256>;
+
+    /// Returns each relayer address with the number of releases it has validly signed.
+    #[method(name = "bridge_relayerStats")]
+    fn relayer_stats(&self, at: Option<BlockHash>) -> RpcResult<Vec<(H160, u64)>>;
+
+    /// Returns whether releasing `amount` for `message_id`, signed as valid until `valid_until`,
+    /// would pass every check other than the relayer signatures.
+    #[method(name = "bridge_canRelease")]
+    fn can_release(
+        &self,
+        message_id: H256,
+        amount: Balance,
+        valid_until: BlockNumber,
+        at: Option<BlockHash>,
+    ) -> RpcResult<ReleaseCheck>;
+
+    /// Returns how many valid, unique relayer signatures `signatures` holds for the release of
+    /// `message_id` valid until `valid_until`, so relayers can check the threshold is met before
+    /// submitting `release`.
+    #[method(name = "bridge_verifySignatures")]
+    fn verify_release_signatures(
+        &self,
+        message_id: H256,
+        valid_until: BlockNumber,
+        signatures: Vec<Bytes>,
+        at: Option<BlockHash>,
+    ) -> RpcResult<u32>;
+}
+
+/// Provides RPC methods to query the bridge pallet.
+pub struct Bridge<C, Block> {
+    client: Arc<C>,
+    _marker: PhantomData<Block>,
+}
+
+impl<C, Block> Bridge<C, Block> {
+    /// Creates a new instance of the bridge RPC helper.
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client, _marker: Default::default() }
+    }
+}
+
+impl<C, Block, AccountId, Balance, BlockNumber>
+    BridgeApiServer<<Block as BlockT>::Hash, AccountId, Balance, BlockNumber> for Bridge<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: BridgeRuntimeApi<Block, AccountId, Balance, BlockNumber>,
+    AccountId: Codec + Send + Sync + 'static,
+    Balance: Codec + Send + Sync + 'static,
+    BlockNumber: Codec + Send + Sync + 'static,
+{
+    fn compute_message_id(
+        &self,
+        sender: AccountId,
+        amount: Balance,
+        eth_recipient: H160,
+        nonce: u64,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<H256> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.compute_message_id(at, sender, amount, eth_recipient, nonce)
+            .map_err(runtime_error_into_rpc_err)?
+            .map(H256::from)
+            .ok_or_else(|| {
+                ErrorObject::owned(INVALID_AMOUNT, "Amount cannot be encoded as u128", None::<()>)
+            })
+    }
+
+    fn relayer_stats(&self, at: Option<Block::Hash>) -> RpcResult<Vec<(H160, u64)>> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.relayer_stats(at).map_err(runtime_error_into_rpc_err)
+    }
+
+    fn can_release(
+        &self,
+        message_id: H256,
+        amount: Balance,
+        valid_until: BlockNumber,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<ReleaseCheck> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.can_release(at, message_id.0, amount, valid_until)
+            .map_err(runtime_error_into_rpc_err)
+    }
+
+    fn verify_release_signatures(
+        &self,
+        message_id: H256,
+        valid_until: BlockNumber,
+        signatures: Vec<Bytes>,
+        at: Option<Block::Hash>,
+    ) -> RpcResult<u32> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        let signatures = signatures.into_iter().map(|sig| sig.0).collect();
+        api.verify_release_signatures(at, message_id.0, valid_until, signatures)
+            .map_err(runtime_error_into_rpc_err)
+    }
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> ErrorObjectOwned {
+    ErrorObject::owned(RUNTIME_ERROR, "Runtime error", Some(format!("{err:?}")))
+}
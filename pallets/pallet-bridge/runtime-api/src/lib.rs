@@ -0,0 +1,46 @@
+//! Runtime API definition for the bridge pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::Codec;
+use sp_core::H160;
+
+pub use pallet_bridge::ReleaseCheck;
+
+sp_api::decl_runtime_apis! {
+    /// Read-only queries against the bridge pallet.
+    pub trait BridgeApi<AccountId, Balance, BlockNumber>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+        BlockNumber: Codec,
+    {
+        /// Returns the message id `lock` would produce for the given arguments, or `None` if
+        /// `amount` cannot be encoded.
+        fn compute_message_id(
+            sender: AccountId,
+            amount: Balance,
+            eth_recipient: H160,
+            nonce: u64,
+        ) -> Option<[u8; 32]>;
+
+        /// Returns each relayer address with the number of releases it has validly signed.
+        fn relayer_stats() -> Vec<(H160, u64)>;
+
+        /// Returns whether releasing `amount` for `message_id`, signed as valid until
+        /// `valid_until`, would pass every check other than the relayer signatures.
+        fn can_release(message_id: [u8; 32], amount: Balance, valid_until: BlockNumber)
+            -> ReleaseCheck;
+
+        /// Returns how many valid, unique relayer signatures `signatures` holds for the release
+        /// of `message_id` valid until `valid_until`, counted as `release` would count them.
+        fn verify_release_signatures(
+            message_id: [u8; 32],
+            valid_until: BlockNumber,
+            signatures: Vec<Vec<u8>>,
+        ) -> u32;
+    }
+}
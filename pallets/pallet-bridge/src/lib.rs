@@ -1,5 +1,6 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -7,28 +8,82 @@ mod tests;
 
 pub use pallet::*;
 const MAX_RELAYERS: u32 = 100;
+/// Target of the pallet's log lines.
+pub(crate) const LOG_TARGET: &str = "runtime::bridge";
 #[frame_support::pallet]
 pub mod pallet {
-    use super::MAX_RELAYERS;
+    use super::{LOG_TARGET, MAX_RELAYERS};
     use frame_support::{
         PalletId,
         pallet_prelude::*,
-        traits::{Currency, ExistenceRequirement::AllowDeath},
+        storage::with_storage_layer,
+        traits::{
+            BalanceStatus, Currency,
+            ExistenceRequirement::{AllowDeath, KeepAlive},
+            ReservableCurrency,
+        },
     };
     use frame_system::pallet_prelude::*;
-    use sp_core::{H160, keccak_256};
+    use sp_core::{H160, H256, keccak_256};
     use sp_io::crypto::secp256k1_ecdsa_recover;
-    use sp_runtime::traits::{AccountIdConversion, SaturatedConversion, Saturating};
-    use sp_std::vec::Vec;
+    use sp_runtime::{
+        SaturatedConversion,
+        traits::{AccountIdConversion, Saturating},
+    };
+    use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
 
     /// Locked message info stored per message id
     #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
-    pub struct LockedInfo<AccountId, Balance> {
+    pub struct LockedInfo<AccountId, Balance, Memo> {
         pub owner: AccountId,     // who locked the funds on Substrate
         pub amount: Balance,      // amount locked (native token)
         pub relayer_fee: Balance, // relayer fee attached to this lock (may be zero)
         pub eth_recipient: H160,  // Ethereum recipient address originally provided
         pub nonce: u64,           // nonce provided by locker (to avoid collisions)
+        pub memo: Memo,           // opaque integrator reference (may be empty)
+    }
+
+    /// How a relayer signature's 32-byte digest is derived from the release digest
+    /// (`Pallet::release_digest`, binding the message id and its expiry) before recovery.
+    #[derive(
+        Encode, Decode, Clone, Copy, PartialEq, Eq, Default, RuntimeDebug, TypeInfo, MaxEncodedLen,
+    )]
+    pub enum SignaturePrefixScheme {
+        /// `personal_sign`: keccak256("\x19Ethereum Signed Message:\n32" || release_digest).
+        #[default]
+        PersonalSign,
+        /// The release digest is signed as-is.
+        Raw,
+        /// EIP-712 typed data `Release(bytes32 messageId,uint64 validUntil)` under the given
+        /// domain separator.
+        Eip712 { domain_separator: [u8; 32] },
+    }
+
+    /// Whether a release of a given message and amount would go through, signatures aside.
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+    pub enum ReleaseCheck {
+        /// The message can be released.
+        Ok,
+        /// The message id was already released.
+        AlreadyProcessed,
+        /// There is no lock for the message id and the release fund cannot cover the amount.
+        NoLockedEntry,
+        /// The lock for the message id holds less than the amount.
+        InsufficientFunds,
+        /// The lock for the message id holds more than the amount; locks are released whole.
+        PartialRelease,
+        /// There is no lock for the message id and paying the amount out of the pallet account
+        /// would take it below the existential deposit.
+        WouldReapPoolAccount,
+        /// The amount exceeds `MaxReleaseAmount`.
+        AmountTooLarge,
+        /// The signatures' `valid_until` block has passed.
+        Expired,
+        /// `valid_until` lies more than `ProcessedRetention` blocks ahead, so the message id
+        /// could be pruned while its signatures are still valid.
+        ValidityTooLong,
     }
 
     #[pallet::config]
@@ -36,26 +91,73 @@ pub mod pallet {
         /// Event type.
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
-        /// Native currency (pallet-balances).
-        type Currency: Currency<Self::AccountId>;
+        /// Native currency (pallet-balances). Locked funds are reserved on the owner's account.
+        type Currency: ReservableCurrency<Self::AccountId>;
 
-        /// Pallet id -> used to derive sovereign account that stores locked funds.
+        /// Pallet id -> used to derive sovereign account that holds relayer fees and the relayer
+        /// fund.
         #[pallet::constant]
         type BridgePalletId: Get<PalletId>;
 
-        /// Relayer signatures threshold (K-of-N).
+        /// Relayer signatures threshold (K-of-N), used until `set_relayers_and_threshold` sets one.
         #[pallet::constant]
         type RelayerThreshold: Get<u32>;
 
         /// Maximum number of signatures accepted in a single release call (to bound weight).
         #[pallet::constant]
         type MaxSignatures: Get<u32>;
+
+        /// Digest scheme relayers use when signing message ids.
+        #[pallet::constant]
+        type SignaturePrefixScheme: Get<SignaturePrefixScheme>;
+
+        /// Maximum length in bytes of the memo attached to a lock.
+        #[pallet::constant]
+        type MaxMemoLen: Get<u32>;
+
+        /// Number of blocks a processed message id is kept for replay protection before
+        /// `on_idle` may prune it. Releases must then expire within this many blocks, so a pruned
+        /// id can never be replayed. Zero disables pruning and leaves validity unbounded.
+        #[pallet::constant]
+        type ProcessedRetention: Get<BlockNumberFor<Self>>;
+
+        /// Maximum number of messages accepted by a single `release_batch` call.
+        #[pallet::constant]
+        type MaxReleaseBatch: Get<u32>;
+
+        /// Minimum number of blocks between two locks by the same account. Zero disables the
+        /// cooldown.
+        #[pallet::constant]
+        type LockCooldown: Get<BlockNumberFor<Self>>;
+
+        /// Maximum number of outstanding locks per account, bounding the `LockedMessages`
+        /// entries a single account can create.
+        #[pallet::constant]
+        type MaxActiveLocksPerAccount: Get<u32>;
+
+        /// Largest amount a single release may pay out. Bigger transfers have to be split into
+        /// separately signed messages, so one faulty signing round cannot drain the pallet.
+        #[pallet::constant]
+        type MaxReleaseAmount: Get<BalanceOf<Self>>;
+
+        /// Origin allowed to set relayers and perform emergency withdrawals.
+        type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+        /// Origin allowed to pause and unpause the bridge.
+        type EmergencyOrigin: EnsureOrigin<Self::RuntimeOrigin>;
     }
 
     pub type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+    pub type MemoOf<T> = BoundedVec<u8, <T as Config>::MaxMemoLen>;
+    /// A relayer signature: 65 bytes r||s||v. Longer inputs fail to decode.
+    pub type Signature = BoundedVec<u8, ConstU32<65>>;
+    /// The signatures of one release, at most `MaxSignatures` of them.
+    pub type SignaturesOf<T> = BoundedVec<Signature, <T as Config>::MaxSignatures>;
+    pub type LockedInfoOf<T> =
+        LockedInfo<<T as frame_system::Config>::AccountId, BalanceOf<T>, MemoOf<T>>;
     /// The current storage version.
-    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
 
     // Pallet storage
     #[pallet::pallet]
@@ -68,22 +170,27 @@ pub mod pallet {
     pub(super) type Relayers<T: Config> =
         StorageValue<_, BoundedVec<H160, ConstU32<{ MAX_RELAYERS }>>, ValueQuery>;
 
+    /// Relayer signatures threshold (K-of-N) in force, `RelayerThreshold` unless set on-chain.
+    #[pallet::storage]
+    #[pallet::getter(fn threshold)]
+    pub(super) type Threshold<T: Config> = StorageValue<_, u32, ValueQuery, T::RelayerThreshold>;
+
     /// Mapping message_id -> LockedInfo (only for Substrate->Ethereum locks).
     #[pallet::storage]
     #[pallet::getter(fn locked)]
-    pub(super) type LockedMessages<T: Config> = StorageMap<
-        _,
-        Blake2_128Concat,
-        [u8; 32],
-        LockedInfo<T::AccountId, BalanceOf<T>>,
-        OptionQuery,
-    >;
-
-    /// Processed message ids (prevents replays for releases coming from Ethereum side).
+    pub(super) type LockedMessages<T: Config> =
+        StorageMap<_, Blake2_128Concat, [u8; 32], LockedInfoOf<T>, OptionQuery>;
+
+    /// Processed message ids (prevents replays for releases coming from Ethereum side),
+    /// mapped to the block they were processed at.
     #[pallet::storage]
     #[pallet::getter(fn processed)]
     pub(super) type ProcessedMessages<T: Config> =
-        StorageMap<_, Blake2_128Concat, [u8; 32], bool, ValueQuery>;
+        StorageMap<_, Blake2_128Concat, [u8; 32], BlockNumberFor<T>, OptionQuery>;
+
+    /// Last message id visited by the `on_idle` pruning sweep; the next sweep resumes after it.
+    #[pallet::storage]
+    pub(super) type ProcessedPruneCursor<T: Config> = StorageValue<_, [u8; 32], OptionQuery>;
 
     /// Total amount of native assets locked for bridging to Ethereum.
     #[pallet::storage]
@@ -95,18 +202,48 @@ pub mod pallet {
     #[pallet::getter(fn total_released)]
     pub(super) type TotalReleased<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
-    /// Paused flag (owner can pause emergency).
+    /// Number of releases each relayer address has validly signed.
+    #[pallet::storage]
+    #[pallet::getter(fn relayer_sign_count)]
+    pub(super) type RelayerSignCount<T: Config> =
+        StorageMap<_, Blake2_128Concat, H160, u64, ValueQuery>;
+
+    /// Block of each account's latest lock, tracked only while `LockCooldown` is non-zero.
     #[pallet::storage]
-    #[pallet::getter(fn paused)]
-    pub(super) type Paused<T: Config> = StorageValue<_, bool, ValueQuery>;
+    #[pallet::getter(fn last_lock_block)]
+    pub(super) type LastLockBlock<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, BlockNumberFor<T>, OptionQuery>;
+
+    /// Number of outstanding entries in `LockedMessages` owned by each account.
+    #[pallet::storage]
+    #[pallet::getter(fn active_lock_count)]
+    pub(super) type ActiveLockCount<T: Config> =
+        StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+    /// Blocks `lock` (outbound flow) while set.
+    #[pallet::storage]
+    #[pallet::getter(fn lock_paused)]
+    pub(super) type LockPaused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// Blocks `release` and `release_batch` (inbound flow) while set.
+    #[pallet::storage]
+    #[pallet::getter(fn release_paused)]
+    pub(super) type ReleasePaused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// Part of the pallet account's balance set aside by `top_up_release_fund` for releases of
+    /// messages without a lock on this chain. Relayer fees and relayer fund top-ups are not
+    /// part of it.
+    #[pallet::storage]
+    #[pallet::getter(fn release_fund)]
+    pub(super) type ReleaseFund<T: Config> = StorageValue<_, BalanceOf<T>, ValueQuery>;
 
     // Events
     #[pallet::event]
     #[pallet::generate_deposit(pub(super) fn deposit_event)]
     pub enum Event<T: Config> {
         /// Funds locked by a user for bridging to ETH.
-        /// (who, amount, relayer_fee, eth_recipient, nonce, message_id)
-        Locked(T::AccountId, BalanceOf<T>, BalanceOf<T>, H160, u64, [u8; 32]),
+        /// (who, amount, relayer_fee, eth_recipient, nonce, message_id, memo)
+        Locked(T::AccountId, BalanceOf<T>, BalanceOf<T>, H160, u64, [u8; 32], MemoOf<T>),
 
         /// Funds released on Substrate (recipient got amount).
         /// (recipient, amount, message_id, number of valid signatures)
@@ -121,14 +258,30 @@ pub mod pallet {
         /// Relayers list updated
         RelayersUpdated(Vec<H160>),
 
+        /// Relayer signatures threshold updated
+        ThresholdUpdated(u32),
+
         /// Relayer fund topped up
         RelayerFundToppedUp(BalanceOf<T>),
 
+        /// Release fund topped up
+        ReleaseFundToppedUp(BalanceOf<T>),
+
         /// Emergency withdraw executed by admin
         EmergencyWithdraw(T::AccountId, BalanceOf<T>),
 
         /// Paused/unpaused toggles
         PausedSet(bool),
+
+        /// Each direction of flow was paused or unpaused independently.
+        DirectionPausedSet { lock: bool, release: bool },
+
+        /// A `release_batch` finished; `skipped` items failed and were left untouched.
+        BatchReleased { released: u32, skipped: u32 },
+
+        /// A lock was returned to its owner's free balance by `refund_lock`. Relayers must not
+        /// mint wXOR for `message_id` once they see this event.
+        LockRefunded { message_id: [u8; 32], owner: T::AccountId, amount: BalanceOf<T> },
     }
 
     // Errors
@@ -150,7 +303,8 @@ pub mod pallet {
         Paused,
         /// Too many signatures provided
         TooManySignatures,
-        /// Overflow on arithmetic (should not happen with saturating ops).
+        /// Amount does not fit in the u128 encoded into message ids, or a lock with the same
+        /// message id already exists.
         Overflow,
         /// Caller not root for admin action
         NotAuthorized,
@@ -158,6 +312,25 @@ pub mod pallet {
         RelayerFundInsufficient,
         /// TooManyRelayers
         TooManyRelayers,
+        /// More items than `MaxReleaseBatch` were passed to `release_batch`.
+        BatchTooLarge,
+        /// The threshold is zero or larger than the relayer set.
+        InvalidConfiguration,
+        /// The caller locked less than `LockCooldown` blocks ago.
+        LockCooldownActive,
+        /// The caller already has `MaxActiveLocksPerAccount` outstanding locks.
+        TooManyActiveLocks,
+        /// Paying the release out of the pallet account would take it below the existential
+        /// deposit.
+        WouldReapPoolAccount,
+        /// The lock or release amount exceeds `MaxReleaseAmount`.
+        ReleaseAmountTooLarge,
+        /// The release's `valid_until` block has passed.
+        MessageExpired,
+        /// The release's `valid_until` lies more than `ProcessedRetention` blocks ahead.
+        ValidityTooLong,
+        /// The release amount is below the locked amount; a lock can only be released whole.
+        PartialRelease,
     }
 
     #[pallet::genesis_config]
@@ -181,6 +354,19 @@ pub mod pallet {
         }
     }
 
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        /// Prune processed message ids older than `ProcessedRetention` using leftover weight.
+        fn on_idle(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            Self::prune_processed(now, remaining_weight)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
+        }
+    }
+
     // Dispatchable functions
     #[pallet::call]
     impl<T: Config> Pallet<T> {
@@ -189,132 +375,113 @@ pub mod pallet {
         /// `eth_recipient` is the 20-byte ethereum recipient (H160).
         /// `relayer_fee` is the portion reserved to reimburse the relayer (may be zero).
         /// `nonce` is any user-chosen nonce to avoid message collisions (recommended).
+        /// `memo` is an opaque reference (e.g. an order id) stored with the lock; may be empty.
+        /// A memo longer than `MaxMemoLen` fails to decode.
+        /// An account can lock at most once every `LockCooldown` blocks and hold at most
+        /// `MaxActiveLocksPerAccount` outstanding locks.
+        /// `amount` may not exceed `MaxReleaseAmount`, since a lock is released whole.
         #[pallet::call_index(0)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(10,3))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(12,5))]
         pub fn lock(
             origin: OriginFor<T>,
             amount: BalanceOf<T>,
             relayer_fee: BalanceOf<T>,
             eth_recipient: H160,
             nonce: u64,
+            memo: MemoOf<T>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            ensure!(!Self::is_paused(), Error::<T>::Paused);
+            ensure!(!LockPaused::<T>::get(), Error::<T>::Paused);
             ensure!(amount > Zero::zero(), Error::<T>::InsufficientBalance);
+            ensure!(amount <= T::MaxReleaseAmount::get(), Error::<T>::ReleaseAmountTooLarge);
+
+            let cooldown = T::LockCooldown::get();
+            let now = frame_system::Pallet::<T>::block_number();
+            if let Some(last) = LastLockBlock::<T>::get(&who) {
+                ensure!(now >= last.saturating_add(cooldown), Error::<T>::LockCooldownActive);
+            }
+            let active_locks = ActiveLockCount::<T>::get(&who);
+            ensure!(
+                active_locks < T::MaxActiveLocksPerAccount::get(),
+                Error::<T>::TooManyActiveLocks
+            );
 
             // Ensure caller has enough free balance for amount + relayer_fee
             let total = amount.saturating_add(relayer_fee);
             let free = T::Currency::free_balance(&who);
             ensure!(free >= total, Error::<T>::InsufficientBalance);
 
-            // Transfer total into pallet account
-            let pallet_acct = Self::account_id();
-            T::Currency::transfer(&who, &pallet_acct, total, AllowDeath)?;
-
-            // Compute canonical message id:
-            // keccak256(chain_id || direction || amount_u128 || substrate_sender_scale ||
-            // eth_recipient || nonce) Use chain_id = 1, direction = 0 for
-            // Substrate->Ethereum per earlier convention.
-            let chain_id: u64 = 1u64;
-            let direction: u8 = 0u8;
-            let amount_u128 = Self::balance_to_u128(&amount)?;
-            let mut enc: Vec<u8> = Vec::new();
-            enc.extend_from_slice(&chain_id.to_be_bytes());
-            enc.extend_from_slice(&direction.to_be_bytes());
-            enc.extend_from_slice(&amount_u128.to_be_bytes());
-            enc.extend_from_slice(&who.encode());
-            enc.extend_from_slice(eth_recipient.as_bytes());
-            enc.extend_from_slice(&nonce.to_be_bytes());
-            let id = keccak_256(&enc);
+            let id = Self::compute_message_id(&who, amount, eth_recipient, nonce)?;
 
             // Store locked info; if entry exists with same id, fail to avoid overwrite
             ensure!(!LockedMessages::<T>::contains_key(id), Error::<T>::Overflow);
 
-            let li = LockedInfo { owner: who.clone(), amount, relayer_fee, eth_recipient, nonce };
+            // Hold the amount on the owner's own account; the relayer fee goes to the pallet
+            // account that reimburses relayers.
+            T::Currency::reserve(&who, amount)?;
+            if !relayer_fee.is_zero() {
+                T::Currency::transfer(&who, &Self::account_id(), relayer_fee, AllowDeath)?;
+            }
+
+            let li = LockedInfo {
+                owner: who.clone(),
+                amount,
+                relayer_fee,
+                eth_recipient,
+                nonce,
+                memo: memo.clone(),
+            };
             LockedMessages::<T>::insert(id, li);
+            ActiveLockCount::<T>::insert(&who, active_locks.saturating_add(1));
+            if !cooldown.is_zero() {
+                LastLockBlock::<T>::insert(&who, now);
+            }
 
             TotalLocked::<T>::mutate(|total| *total = total.saturating_add(amount));
 
-            Self::deposit_event(Event::Locked(who, amount, relayer_fee, eth_recipient, nonce, id));
+            Self::deposit_event(Event::Locked(
+                who,
+                amount,
+                relayer_fee,
+                eth_recipient,
+                nonce,
+                id,
+                memo,
+            ));
             Ok(())
         }
 
         /// Release locked native tokens on Substrate after verifying K-of-N relayer signatures over
         /// the message id. `message_id` is the 32-byte message identifier (as emitted by
         /// Ethereum or canonicalized on ETH side). `recipient` will receive the unlocked
-        /// native tokens. `amount` expected amount to release (must be <= locked amount).
-        /// `signatures` Vec<Vec<u8>> — each signature is 65 bytes r||s||v (v = 27/28 or 0/1).
+        /// native tokens. `amount` expected amount to release (must equal the locked amount).
+        /// `valid_until` is the last block the relayers' signatures are valid for; it is signed
+        /// together with `message_id` and may be at most `ProcessedRetention` blocks ahead.
+        /// `signatures` — each signature is 65 bytes r||s||v (v = 27/28 or 0/1); more than
+        /// `MaxSignatures` signatures or a longer signature is rejected when the call is decoded.
+        /// If `message_id` names a lock on this chain, the whole lock is repatriated from the lock
+        /// owner's reserve; otherwise it is paid from the release fund.
         #[pallet::call_index(1)]
-        #[pallet::weight(Weight::from_all(10_000) + T::DbWeight::get().reads_writes(2,3))]
+        #[pallet::weight(Pallet::<T>::release_weight())]
         pub fn release(
             origin: OriginFor<T>,
             message_id: [u8; 32],
             recipient: T::AccountId,
             amount: BalanceOf<T>,
-            signatures: Vec<Vec<u8>>,
+            valid_until: BlockNumberFor<T>,
+            signatures: SignaturesOf<T>,
         ) -> DispatchResult {
             let _submitter = ensure_signed(origin)?;
-            ensure!(!Self::is_paused(), Error::<T>::Paused);
-
-            // Check processed
-            ensure!(!ProcessedMessages::<T>::get(message_id), Error::<T>::MessageAlreadyProcessed);
-            // Validate number of signatures
-            let sig_count = signatures.len() as u32;
-            ensure!(sig_count <= T::MaxSignatures::get(), Error::<T>::TooManySignatures);
-
-            // Verify signatures: recover H160 and count unique valid relayers
-            let relayers = Relayers::<T>::get();
-            let thresh = T::RelayerThreshold::get();
-            let mut seen: Vec<H160> = Vec::new();
-            let mut valid: u32 = 0;
-
-            for sig in signatures.iter() {
-                // signature must be 65 bytes
-                if sig.len() != 65 {
-                    continue;
-                }
-                match Self::ecdsa_recover_h160(sig.as_slice(), &message_id) {
-                    Ok(addr) =>
-                        if relayers.contains(&addr) && !seen.contains(&addr) {
-                            seen.push(addr);
-                            valid = valid.saturating_add(1);
-                        },
-                    Err(_) => {
-                        // ignore invalid signature and continue; final check below ensures
-                        // threshold
-                        continue;
-                    },
-                }
-            }
-
-            ensure!(valid >= thresh, Error::<T>::ThresholdNotMet);
-
-            // Transfer amount from pallet account to recipient
-            let pallet_acct = Self::account_id();
-
-            // Double-check that pallet account has balance (should, since locked was previously
-            // transferred)
-            let pallet_balance = T::Currency::free_balance(&pallet_acct);
-            ensure!(pallet_balance >= amount, Error::<T>::InsufficientLockedAmount);
-
-            T::Currency::transfer(&pallet_acct, &recipient, amount, AllowDeath)?;
-
-            // mark processed to avoid replays
-            ProcessedMessages::<T>::insert(message_id, true);
+            ensure!(!ReleasePaused::<T>::get(), Error::<T>::Paused);
 
-            // total released amount
-            TotalReleased::<T>::mutate(|total| *total = total.saturating_add(amount));
-
-            Self::deposit_event(Event::Released(recipient.clone(), amount, message_id, valid));
-
-            Ok(())
+            Self::do_release(message_id, recipient, amount, valid_until, signatures)
         }
 
-        /// Admin: set relayer list (root)
+        /// Admin: set relayer list (`AdminOrigin`)
         #[pallet::call_index(2)]
         #[pallet::weight(T::DbWeight::get().reads_writes(1,3))]
         pub fn set_relayers(origin: OriginFor<T>, relayers: Vec<H160>) -> DispatchResult {
-            ensure_root(origin)?;
+            T::AdminOrigin::ensure_origin(origin)?;
             let bounded_relayers: BoundedVec<H160, ConstU32<MAX_RELAYERS>> =
                 relayers.clone().try_into().map_err(|_| Error::<T>::TooManyRelayers)?;
 
@@ -336,7 +503,7 @@ pub mod pallet {
         }
 
         /// Admin: emergency withdraw some native tokens from the pallet account to an address
-        /// (root)
+        /// (`AdminOrigin`)
         #[pallet::call_index(4)]
         #[pallet::weight(T::DbWeight::get().reads_writes(2,23))]
         pub fn emergency_withdraw(
@@ -344,66 +511,550 @@ pub mod pallet {
             to: T::AccountId,
             amount: BalanceOf<T>,
         ) -> DispatchResult {
-            ensure_root(origin)?;
+            T::AdminOrigin::ensure_origin(origin)?;
             let pallet_acct = Self::account_id();
             // ensure pallet has enough
             let bal = T::Currency::free_balance(&pallet_acct);
             ensure!(bal >= amount, Error::<T>::InsufficientBalance);
             T::Currency::transfer(&pallet_acct, &to, amount, AllowDeath)?;
+            Self::cap_release_fund();
 
             Self::deposit_event(Event::EmergencyWithdraw(to, amount));
             Ok(())
         }
 
-        /// Admin: pause/unpause bridge operations (root)
+        /// Admin: pause/unpause both directions of bridge operations (`EmergencyOrigin`)
         #[pallet::weight(T::DbWeight::get().reads_writes(1,13))]
         #[pallet::call_index(5)]
         pub fn set_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
-            ensure_root(origin)?;
-            Paused::<T>::put(paused);
+            T::EmergencyOrigin::ensure_origin(origin)?;
+            LockPaused::<T>::put(paused);
+            ReleasePaused::<T>::put(paused);
             Self::deposit_event(Event::PausedSet(paused));
             Ok(())
         }
+
+        /// Admin: pause/unpause `lock` and `release` independently (`EmergencyOrigin`), e.g. to
+        /// stop inbound releases during an incident while outbound locks keep draining.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::DbWeight::get().writes(2))]
+        pub fn set_direction_paused(
+            origin: OriginFor<T>,
+            lock: bool,
+            release: bool,
+        ) -> DispatchResult {
+            T::EmergencyOrigin::ensure_origin(origin)?;
+            LockPaused::<T>::put(lock);
+            ReleasePaused::<T>::put(release);
+            Self::deposit_event(Event::DirectionPausedSet { lock, release });
+            Ok(())
+        }
+
+        /// Release several messages in one call, e.g. while relayers catch up on a backlog.
+        /// Each `(message_id, recipient, amount, valid_until, signatures)` item is processed as an
+        /// independent `release`: an item that fails (including an already processed message) is
+        /// skipped without affecting the others. At most `MaxReleaseBatch` items are accepted.
+        #[pallet::call_index(6)]
+        #[pallet::weight(Pallet::<T>::release_weight().saturating_mul(
+            (items.len() as u64).min(T::MaxReleaseBatch::get() as u64)
+        ))]
+        pub fn release_batch(
+            origin: OriginFor<T>,
+            items: Vec<([u8; 32], T::AccountId, BalanceOf<T>, BlockNumberFor<T>, SignaturesOf<T>)>,
+        ) -> DispatchResult {
+            let _submitter = ensure_signed(origin)?;
+            ensure!(!ReleasePaused::<T>::get(), Error::<T>::Paused);
+            ensure!(items.len() as u32 <= T::MaxReleaseBatch::get(), Error::<T>::BatchTooLarge);
+
+            let mut released: u32 = 0;
+            let mut skipped: u32 = 0;
+            for (message_id, recipient, amount, valid_until, signatures) in items {
+                let result = with_storage_layer(|| {
+                    Self::do_release(message_id, recipient, amount, valid_until, signatures)
+                });
+                if result.is_ok() {
+                    released = released.saturating_add(1);
+                } else {
+                    skipped = skipped.saturating_add(1);
+                }
+            }
+
+            Self::deposit_event(Event::BatchReleased { released, skipped });
+            Ok(())
+        }
+
+        /// Admin: replace the relayer list and the threshold together (`AdminOrigin`), so the
+        /// quorum is never left unreachable in between. Requires `1 <= threshold <= relayers`.
+        #[pallet::call_index(8)]
+        #[pallet::weight(T::DbWeight::get().writes(2))]
+        pub fn set_relayers_and_threshold(
+            origin: OriginFor<T>,
+            relayers: Vec<H160>,
+            threshold: u32,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(
+                threshold >= 1 && threshold as usize <= relayers.len(),
+                Error::<T>::InvalidConfiguration
+            );
+            let bounded_relayers: BoundedVec<H160, ConstU32<MAX_RELAYERS>> =
+                relayers.clone().try_into().map_err(|_| Error::<T>::TooManyRelayers)?;
+
+            Relayers::<T>::put(&bounded_relayers);
+            Threshold::<T>::put(threshold);
+            Self::deposit_event(Event::RelayersUpdated(relayers));
+            Self::deposit_event(Event::ThresholdUpdated(threshold));
+            Ok(())
+        }
+
+        /// Admin: return a lock that will never be released on Ethereum to its owner
+        /// (`AdminOrigin`). The locked amount is unreserved on the owner's account and
+        /// `message_id` is marked processed so it can no longer be released; the relayer fee
+        /// stays with the pallet account.
+        ///
+        /// Break-glass only: the chain cannot tell whether the lock was already relayed, so
+        /// refunding a lock whose wXOR was minted pays it out twice. Use it only once the
+        /// relayers confirm they never minted `message_id`; they watch `LockRefunded` and drop
+        /// the lock from their queue.
+        #[pallet::call_index(9)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(4, 5))]
+        pub fn refund_lock(origin: OriginFor<T>, message_id: [u8; 32]) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            let locked = LockedMessages::<T>::get(message_id).ok_or(Error::<T>::NoLockedEntry)?;
+
+            let missing = T::Currency::unreserve(&locked.owner, locked.amount);
+            ensure!(missing.is_zero(), Error::<T>::InsufficientLockedAmount);
+            Self::remove_lock(message_id, &locked.owner, locked.amount);
+            ProcessedMessages::<T>::insert(message_id, frame_system::Pallet::<T>::block_number());
+
+            Self::deposit_event(Event::LockRefunded {
+                message_id,
+                owner: locked.owner,
+                amount: locked.amount,
+            });
+            Ok(())
+        }
+
+        /// Add funds to the release fund that pays releases of messages without a lock on this
+        /// chain, by transferring from the caller to the pallet account.
+        #[pallet::call_index(10)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(3,3))]
+        pub fn top_up_release_fund(origin: OriginFor<T>, amount: BalanceOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(amount > Zero::zero(), Error::<T>::InsufficientBalance);
+            T::Currency::transfer(&who, &Self::account_id(), amount, AllowDeath)?;
+            ReleaseFund::<T>::mutate(|fund| *fund = fund.saturating_add(amount));
+            Self::deposit_event(Event::ReleaseFundToppedUp(amount));
+            Ok(())
+        }
     }
 
     // Implementation details
     impl<T: Config> Pallet<T> {
+        /// Weight of a single release, bounded by `MaxSignatures` recoveries.
+        fn release_weight() -> Weight {
+            Weight::from_all(10_000) +
+                T::DbWeight::get().reads_writes(6, 6 + T::MaxSignatures::get() as u64)
+        }
+
+        /// Verify the relayer signatures over `message_id` and pay out `amount` to `recipient`.
+        /// Shared by `release` and `release_batch`.
+        fn do_release(
+            message_id: [u8; 32],
+            recipient: T::AccountId,
+            amount: BalanceOf<T>,
+            valid_until: BlockNumberFor<T>,
+            signatures: SignaturesOf<T>,
+        ) -> DispatchResult {
+            match Self::can_release(message_id, amount, valid_until) {
+                ReleaseCheck::Ok => {},
+                ReleaseCheck::AlreadyProcessed =>
+                    return Err(Error::<T>::MessageAlreadyProcessed.into()),
+                ReleaseCheck::NoLockedEntry | ReleaseCheck::InsufficientFunds =>
+                    return Err(Error::<T>::InsufficientLockedAmount.into()),
+                ReleaseCheck::PartialRelease => return Err(Error::<T>::PartialRelease.into()),
+                ReleaseCheck::WouldReapPoolAccount =>
+                    return Err(Error::<T>::WouldReapPoolAccount.into()),
+                ReleaseCheck::AmountTooLarge =>
+                    return Err(Error::<T>::ReleaseAmountTooLarge.into()),
+                ReleaseCheck::Expired => return Err(Error::<T>::MessageExpired.into()),
+                ReleaseCheck::ValidityTooLong => return Err(Error::<T>::ValidityTooLong.into()),
+            }
+            // At most `MaxSignatures`, as bounded by `SignaturesOf`
+            let sig_count = signatures.len() as u32;
+            // Too few signatures can never meet the threshold: skip the costly recovery
+            let thresh = Threshold::<T>::get();
+            if sig_count < thresh {
+                log::debug!(
+                    target: LOG_TARGET,
+                    "release message_id={:?}: received={} below threshold={}",
+                    H256(message_id),
+                    sig_count,
+                    thresh,
+                );
+                return Err(Error::<T>::ThresholdNotMet.into());
+            }
+
+            // Verify signatures: recover H160 and count unique valid relayers
+            let relayers = Relayers::<T>::get();
+            let (seen, distinct, unrecoverable) = Self::recover_relayers(
+                &message_id,
+                valid_until.saturated_into(),
+                &signatures,
+                &relayers,
+            );
+            // `seen` holds each relayer once, so this never exceeds the relayer set.
+            let valid = seen.len() as u32;
+            let malformed = signatures.iter().filter(|sig| sig.len() != 65).count() as u32;
+            let duplicates = sig_count.saturating_sub(malformed).saturating_sub(distinct);
+
+            // Counts only: neither the signatures nor the recovered addresses are logged.
+            log::debug!(
+                target: LOG_TARGET,
+                "release message_id={:?}: received={} malformed={} duplicates={} unrecoverable={} \
+                 recovered_relayers={} threshold={}",
+                H256(message_id),
+                sig_count,
+                malformed,
+                duplicates,
+                unrecoverable,
+                valid,
+                thresh,
+            );
+
+            ensure!(valid >= thresh, Error::<T>::ThresholdNotMet);
+
+            if let Some(locked) = LockedMessages::<T>::get(message_id) {
+                // Release the whole lock out of the owner's reserve backing it; `can_release`
+                // ensured `amount` is the locked amount.
+                let unmoved = T::Currency::repatriate_reserved(
+                    &locked.owner,
+                    &recipient,
+                    amount,
+                    BalanceStatus::Free,
+                )?;
+                ensure!(unmoved.is_zero(), Error::<T>::InsufficientLockedAmount);
+                Self::remove_lock(message_id, &locked.owner, amount);
+            } else {
+                // No lock on this side: pay out of the release fund held by the pallet account,
+                // which must stay alive
+                T::Currency::transfer(&Self::account_id(), &recipient, amount, KeepAlive)?;
+                ReleaseFund::<T>::mutate(|fund| *fund = fund.saturating_sub(amount));
+            }
+
+            // mark processed to avoid replays
+            ProcessedMessages::<T>::insert(message_id, frame_system::Pallet::<T>::block_number());
+
+            // total released amount
+            TotalReleased::<T>::mutate(|total| *total = total.saturating_add(amount));
+
+            // credit every relayer whose signature counted towards this release
+            for addr in seen.iter() {
+                RelayerSignCount::<T>::mutate(addr, |count| *count = count.saturating_add(1));
+            }
+
+            Self::deposit_event(Event::Released(recipient.clone(), amount, message_id, valid));
+
+            Ok(())
+        }
+
+        /// Shrink `ReleaseFund` to what the pallet account still holds after paying out of it
+        /// for something other than a release.
+        fn cap_release_fund() {
+            let free = T::Currency::free_balance(&Self::account_id());
+            ReleaseFund::<T>::mutate(|fund| *fund = (*fund).min(free));
+        }
+
+        /// Drop the lock `message_id` of `owner` holding `amount` from the lock bookkeeping.
+        fn remove_lock(message_id: [u8; 32], owner: &T::AccountId, amount: BalanceOf<T>) {
+            LockedMessages::<T>::remove(message_id);
+            ActiveLockCount::<T>::mutate_exists(owner, |count| {
+                *count = count.and_then(|n| n.checked_sub(1)).filter(|n| *n > 0);
+            });
+            TotalLocked::<T>::mutate(|total| *total = total.saturating_sub(amount));
+        }
+        /// Whether releasing `amount` for `message_id`, signed as valid until `valid_until`,
+        /// would pass every check other than the relayer signatures. `release` runs the same
+        /// checks.
+        pub fn can_release(
+            message_id: [u8; 32],
+            amount: BalanceOf<T>,
+            valid_until: BlockNumberFor<T>,
+        ) -> ReleaseCheck {
+            if ProcessedMessages::<T>::contains_key(message_id) {
+                return ReleaseCheck::AlreadyProcessed;
+            }
+            let now = frame_system::Pallet::<T>::block_number();
+            if now > valid_until {
+                return ReleaseCheck::Expired;
+            }
+            let retention = T::ProcessedRetention::get();
+            if !retention.is_zero() && valid_until > now.saturating_add(retention) {
+                return ReleaseCheck::ValidityTooLong;
+            }
+            if amount > T::MaxReleaseAmount::get() {
+                return ReleaseCheck::AmountTooLarge;
+            }
+            match LockedMessages::<T>::get(message_id) {
+                Some(locked) if amount > locked.amount => ReleaseCheck::InsufficientFunds,
+                Some(locked) if amount < locked.amount => ReleaseCheck::PartialRelease,
+                Some(_) => ReleaseCheck::Ok,
+                None => {
+                    let free = T::Currency::free_balance(&Self::account_id());
+                    if ReleaseFund::<T>::get() < amount || free < amount {
+                        ReleaseCheck::NoLockedEntry
+                    } else if free - amount < T::Currency::minimum_balance() {
+                        ReleaseCheck::WouldReapPoolAccount
+                    } else {
+                        ReleaseCheck::Ok
+                    }
+                },
+            }
+        }
+
+        /// The `relayers` that validly signed `message_id`, each once, in submission order,
+        /// together with the number of distinct well-formed signatures and how many of those
+        /// failed to recover. Shared by `do_release` and `verify_release_signatures`.
+        fn recover_relayers(
+            message_id: &[u8; 32],
+            valid_until: u64,
+            signatures: &[Signature],
+            relayers: &[H160],
+        ) -> (Vec<H160>, u32, u32) {
+            let distinct = Self::distinct_signatures(signatures);
+            let distinct_count = distinct.len() as u32;
+            let mut seen: Vec<H160> = Vec::new();
+            let mut unrecoverable: u32 = 0;
+
+            for sig in distinct {
+                match Self::ecdsa_recover_h160(sig, message_id, valid_until) {
+                    Ok(addr) =>
+                        if relayers.contains(&addr) && !seen.contains(&addr) {
+                            seen.push(addr);
+                        },
+                    // ignore invalid signature and continue; the caller checks the threshold
+                    Err(_) => unrecoverable = unrecoverable.saturating_add(1),
+                }
+            }
+            (seen, distinct_count, unrecoverable)
+        }
+
+        /// Number of valid, unique relayer signatures over `message_id` and `valid_until`,
+        /// counted exactly as `release` counts them, without touching state. Signatures longer
+        /// than 65 bytes, which `release` cannot decode, are ignored.
+        pub fn verify_release_signatures(
+            message_id: [u8; 32],
+            valid_until: BlockNumberFor<T>,
+            signatures: Vec<Vec<u8>>,
+        ) -> u32 {
+            let signatures: Vec<Signature> =
+                signatures.into_iter().filter_map(|sig| sig.try_into().ok()).collect();
+            let relayers = Relayers::<T>::get();
+            let (seen, _, _) = Self::recover_relayers(
+                &message_id,
+                valid_until.saturated_into(),
+                &signatures,
+                &relayers,
+            );
+            seen.len() as u32
+        }
+
+        /// Well-formed (65-byte) signatures in submission order, with byte-identical repeats
+        /// dropped so each one is recovered at most once.
+        pub(crate) fn distinct_signatures(signatures: &[Signature]) -> Vec<&[u8]> {
+            let mut unique = BTreeSet::new();
+            signatures
+                .iter()
+                .map(|sig| sig.as_slice())
+                .filter(|sig| sig.len() == 65 && unique.insert(*sig))
+                .collect()
+        }
+
         /// Derived pallet account id.
         pub fn account_id() -> T::AccountId {
             T::BridgePalletId::get().into_account_truncating()
         }
 
-        /// Convenience: check paused flag
+        /// Accounting invariants checked by `try_state`:
+        /// - `TotalLocked` is the sum of the amounts of all `LockedMessages`;
+        /// - every lock owner's reserved balance covers the locks they still hold, since releases
+        ///   are paid out of that reserve;
+        /// - the relayer set holds at most `MAX_RELAYERS` addresses.
+        #[cfg(any(feature = "try-runtime", test))]
+        pub(crate) fn do_try_state() -> Result<(), sp_runtime::TryRuntimeError> {
+            use sp_std::collections::btree_map::BTreeMap;
+
+            let mut total = BalanceOf::<T>::zero();
+            let mut per_owner = BTreeMap::<T::AccountId, BalanceOf<T>>::new();
+            for locked in LockedMessages::<T>::iter_values() {
+                total = total.saturating_add(locked.amount);
+                let owned = per_owner.entry(locked.owner).or_default();
+                *owned = owned.saturating_add(locked.amount);
+            }
+            ensure!(
+                total == TotalLocked::<T>::get(),
+                "TotalLocked does not match the sum of LockedMessages"
+            );
+            for (owner, locked) in per_owner {
+                ensure!(
+                    T::Currency::reserved_balance(&owner) >= locked,
+                    "a lock owner's reserve does not cover their locks"
+                );
+            }
+            ensure!(
+                T::Currency::free_balance(&Self::account_id()) >= ReleaseFund::<T>::get(),
+                "the pallet account does not cover the release fund"
+            );
+            ensure!(
+                Relayers::<T>::decode_len().unwrap_or_default() <= MAX_RELAYERS as usize,
+                "more than MAX_RELAYERS relayers"
+            );
+            Ok(())
+        }
+
+        /// Remove processed message ids older than `ProcessedRetention`, resuming from the cursor
+        /// left by the previous sweep and stopping once `remaining_weight` is used up.
+        pub(crate) fn prune_processed(now: BlockNumberFor<T>, remaining_weight: Weight) -> Weight {
+            let retention = T::ProcessedRetention::get();
+            let db = T::DbWeight::get();
+            // cursor read + write
+            let mut used = db.reads_writes(1, 1);
+            let per_entry = db.reads_writes(1, 1);
+            if retention.is_zero() || remaining_weight.any_lt(used.saturating_add(per_entry)) {
+                return Weight::zero();
+            }
+
+            let mut iter = match ProcessedPruneCursor::<T>::get() {
+                Some(cursor) => ProcessedMessages::<T>::iter_from(
+                    ProcessedMessages::<T>::hashed_key_for(cursor),
+                ),
+                None => ProcessedMessages::<T>::iter(),
+            };
+            let mut cursor = None;
+            while !remaining_weight.any_lt(used.saturating_add(per_entry)) {
+                used = used.saturating_add(per_entry);
+                match iter.next() {
+                    Some((message_id, processed_at)) => {
+                        if now.saturating_sub(processed_at) > retention {
+                            ProcessedMessages::<T>::remove(message_id);
+                        }
+                        cursor = Some(message_id);
+                    },
+                    None => {
+                        // reached the end of the map, start over on the next sweep
+                        cursor = None;
+                        break;
+                    },
+                }
+            }
+
+            ProcessedPruneCursor::<T>::set(cursor);
+            used
+        }
+
+        /// Every relayer address that has signed a release, with its signed-release count.
+        pub fn relayer_stats() -> Vec<(H160, u64)> {
+            RelayerSignCount::<T>::iter().collect()
+        }
+
+        /// Convenience: whether both directions are paused
         pub fn is_paused() -> bool {
-            Paused::<T>::get()
+            LockPaused::<T>::get() && ReleasePaused::<T>::get()
+        }
+
+        /// Compute the canonical message id produced by `lock`:
+        /// keccak256(chain_id || direction || amount_u128 || substrate_sender_scale ||
+        /// eth_recipient || nonce). Uses chain_id = 1, direction = 0 for
+        /// Substrate->Ethereum per earlier convention.
+        pub fn compute_message_id(
+            sender: &T::AccountId,
+            amount: BalanceOf<T>,
+            eth_recipient: H160,
+            nonce: u64,
+        ) -> Result<[u8; 32], Error<T>> {
+            let chain_id: u64 = 1u64;
+            let direction: u8 = 0u8;
+            let amount_u128 = Self::balance_to_u128(&amount)?;
+            let mut enc: Vec<u8> = Vec::new();
+            enc.extend_from_slice(&chain_id.to_be_bytes());
+            enc.extend_from_slice(&direction.to_be_bytes());
+            enc.extend_from_slice(&amount_u128.to_be_bytes());
+            enc.extend_from_slice(&sender.encode());
+            enc.extend_from_slice(eth_recipient.as_bytes());
+            enc.extend_from_slice(&nonce.to_be_bytes());
+            Ok(keccak_256(&enc))
         }
 
         /// Convert BalanceOf<T> -> u128 for canonical hashing / encoding.
-        /// Assumes Balance fits within u128 (common). If your runtime uses larger types adapt
-        /// accordingly.
+        /// Fails with `Overflow` if the runtime's Balance holds a value above u128::MAX.
         pub fn balance_to_u128(b: &BalanceOf<T>) -> Result<u128, Error<T>> {
-            // saturated_into will not panic; we treat values > u128::MAX as overflow error
-            let v: u128 = (*b).saturated_into::<u128>();
-            Ok(v)
+            Self::amount_to_u128(*b)
+        }
+
+        /// Convert any amount to the u128 encoded into message ids, failing with `Overflow`
+        /// rather than saturating so the id always matches the Ethereum side.
+        pub fn amount_to_u128<A: TryInto<u128>>(amount: A) -> Result<u128, Error<T>> {
+            amount.try_into().map_err(|_| Error::<T>::Overflow)
+        }
+
+        /// The release a relayer approves: keccak256(message_id || valid_until as u64
+        /// big-endian). Binding the expiry into what is signed lets processed ids be pruned
+        /// once it has passed.
+        pub fn release_digest(message_id: &[u8; 32], valid_until: u64) -> [u8; 32] {
+            let mut enc: Vec<u8> = Vec::new();
+            enc.extend_from_slice(message_id);
+            enc.extend_from_slice(&valid_until.to_be_bytes());
+            keccak_256(&enc)
         }
 
-        /// Recover Ethereum-style ECDSA signer H160 from signature and message id (32 bytes).
+        /// Digest relayers sign for the release of `message_id` valid until `valid_until`, under
+        /// the configured `SignaturePrefixScheme`.
+        pub fn signing_hash(message_id: &[u8; 32], valid_until: u64) -> [u8; 32] {
+            match T::SignaturePrefixScheme::get() {
+                SignaturePrefixScheme::PersonalSign => {
+                    let mut prefixed_message = Vec::new();
+                    prefixed_message.extend_from_slice(b"\x19Ethereum Signed Message:\n32");
+                    prefixed_message
+                        .extend_from_slice(&Self::release_digest(message_id, valid_until));
+                    keccak_256(&prefixed_message)
+                },
+                SignaturePrefixScheme::Raw => Self::release_digest(message_id, valid_until),
+                SignaturePrefixScheme::Eip712 { domain_separator } => {
+                    let mut struct_data = Vec::new();
+                    struct_data.extend_from_slice(&keccak_256(
+                        b"Release(bytes32 messageId,uint64 validUntil)",
+                    ));
+                    struct_data.extend_from_slice(message_id);
+                    // ABI-encoded as a 32-byte word
+                    struct_data.extend_from_slice(&[0u8; 24]);
+                    struct_data.extend_from_slice(&valid_until.to_be_bytes());
+                    let struct_hash = keccak_256(&struct_data);
+
+                    let mut typed_data = Vec::new();
+                    typed_data.extend_from_slice(b"\x19\x01");
+                    typed_data.extend_from_slice(&domain_separator);
+                    typed_data.extend_from_slice(&struct_hash);
+                    keccak_256(&typed_data)
+                },
+            }
+        }
+
+        /// Recover Ethereum-style ECDSA signer H160 from a signature over the release of
+        /// `message_id` valid until `valid_until`.
         /// Expects a 65-byte signature (r||s||v) where v is 27/28 or 0/1.
-        pub fn ecdsa_recover_h160(sig: &[u8], message_id: &[u8; 32]) -> Result<H160, Error<T>> {
+        pub fn ecdsa_recover_h160(
+            sig: &[u8],
+            message_id: &[u8; 32],
+            valid_until: u64,
+        ) -> Result<H160, Error<T>> {
             if sig.len() != 65 {
                 return Err(Error::<T>::InvalidSignature);
             }
-            // 1. Construct the prefixed message
-            let mut prefixed_message = Vec::new();
-            prefixed_message.extend_from_slice(b"\x19Ethereum Signed Message:\n32");
-            prefixed_message.extend_from_slice(message_id);
-
-            // 2. Hash the prefixed message
-            let final_hash = keccak_256(&prefixed_message);
+            let final_hash = Self::signing_hash(message_id, valid_until);
 
             let mut sig_arr = [0u8; 65];
             sig_arr.copy_from_slice(&sig[0..65]);
-            // Note: secp256k1_ecdsa_recover expects a 32-byte message. We pass the raw message_id.
+            // Note: secp256k1_ecdsa_recover expects a 32-byte message digest.
             match secp256k1_ecdsa_recover(&sig_arr, &final_hash) {
                 Ok(pubkey) => {
                     let hash = keccak_256(&pubkey);
@@ -0,0 +1,263 @@
+//! Storage migrations for the bridge pallet.
+
+use crate::{
+    ActiveLockCount, Config, LOG_TARGET, LockPaused, LockedMessages, Pallet, ProcessedMessages,
+    ReleasePaused,
+};
+use frame_support::{
+    migrations::VersionedMigration,
+    pallet_prelude::*,
+    traits::{Currency, UncheckedOnRuntimeUpgrade},
+};
+use sp_std::marker::PhantomData;
+
+pub mod v2 {
+    use super::*;
+    use crate::{BalanceOf, LockedInfo};
+    use sp_core::H160;
+
+    /// `LockedInfo` as stored before locks carried a memo.
+    #[derive(Encode, Decode)]
+    pub struct LockedInfoV1<AccountId, Balance> {
+        pub owner: AccountId,
+        pub amount: Balance,
+        pub relayer_fee: Balance,
+        pub eth_recipient: H160,
+        pub nonce: u64,
+    }
+
+    /// Re-encodes every `LockedMessages` entry with an empty memo, so locks created before
+    /// memos existed keep decoding. Runs before any other bridge migration, since later ones
+    /// iterate `LockedMessages`.
+    pub struct InnerMigrateV1ToV2<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV1ToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut translated: u64 = 0;
+            LockedMessages::<T>::translate::<LockedInfoV1<T::AccountId, BalanceOf<T>>, _>(
+                |_, old| {
+                    translated += 1;
+                    Some(LockedInfo {
+                        owner: old.owner,
+                        amount: old.amount,
+                        relayer_fee: old.relayer_fee,
+                        eth_recipient: old.eth_recipient,
+                        nonce: old.nonce,
+                        memo: Default::default(),
+                    })
+                },
+            );
+            T::DbWeight::get().reads_writes(translated, translated)
+        }
+    }
+
+    /// [`InnerMigrateV1ToV2`] gated on the on-chain storage version being 1.
+    pub type MigrateV1ToV2<T> = VersionedMigration<
+        1,
+        2,
+        InnerMigrateV1ToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+pub mod v3 {
+    use super::*;
+
+    /// Converts `ProcessedMessages` from `bool` flags to the block a message was processed at.
+    /// Existing entries are stamped with the upgrade block, so they stay protected for a full
+    /// `ProcessedRetention` window after the upgrade.
+    pub struct InnerMigrateV2ToV3<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV2ToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let now = frame_system::Pallet::<T>::block_number();
+            let mut translated: u64 = 0;
+            ProcessedMessages::<T>::translate::<bool, _>(|_, processed| {
+                translated += 1;
+                processed.then_some(now)
+            });
+            T::DbWeight::get().reads_writes(translated + 1, translated)
+        }
+    }
+
+    /// [`InnerMigrateV2ToV3`] gated on the on-chain storage version being 2.
+    pub type MigrateV2ToV3<T> = VersionedMigration<
+        2,
+        3,
+        InnerMigrateV2ToV3<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+pub mod v4 {
+    use super::*;
+    use crate::{BalanceOf, ReleaseFund, TotalLocked};
+    use sp_runtime::traits::{Saturating, Zero};
+    #[cfg(feature = "try-runtime")]
+    use sp_std::vec::Vec;
+
+    /// Most locks the migration settles in one block. Each costs a read and a write, about
+    /// 125µs under `RocksDbWeight`, so 10_000 locks take about 1.25s of the runtime's 2s block.
+    /// `pre_upgrade` fails above it; a chain with more locks needs a multi-block migration.
+    pub const MAX_SETTLED_LOCKS: u32 = 10_000;
+
+    /// Settles the locks made before v4. Back then `lock` moved the locked amount into the
+    /// pallet account, and that balance now backs the wXOR minted for those locks on Ethereum.
+    /// Inbound releases carry Bridge.sol message ids that never match a lock, so they are paid
+    /// from `ReleaseFund`. The migration therefore leaves the funds in the pallet account and
+    /// credits them to `ReleaseFund`, then drops the settled locks from `LockedMessages` and
+    /// `TotalLocked`. Relayers act on the `Locked` events, so a lock still waiting to be
+    /// relayed is minted as before. If the pallet account holds less than the settled amount
+    /// above its existential deposit and `ReleaseFund`, only what it holds is credited and the
+    /// shortfall is logged as an error.
+    pub struct InnerMigrateV3ToV4<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV3ToV4<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut locks: u64 = 0;
+            let mut settled = BalanceOf::<T>::zero();
+            for (_, locked) in LockedMessages::<T>::drain() {
+                locks += 1;
+                settled = settled.saturating_add(locked.amount);
+            }
+            TotalLocked::<T>::mutate(|total| *total = total.saturating_sub(settled));
+
+            let available = T::Currency::free_balance(&Pallet::<T>::account_id())
+                .saturating_sub(T::Currency::minimum_balance())
+                .saturating_sub(ReleaseFund::<T>::get());
+            let credited = settled.min(available);
+            if credited < settled {
+                log::error!(
+                    target: LOG_TARGET,
+                    "bridge v4 migration: pallet account is {:?} short of the {:?} settled",
+                    settled.saturating_sub(credited),
+                    settled,
+                );
+            }
+            ReleaseFund::<T>::mutate(|fund| *fund = fund.saturating_add(credited));
+
+            // per lock: the entry; plus the pallet account, `TotalLocked` and `ReleaseFund`
+            T::DbWeight::get().reads_writes(locks + 3, locks + 2)
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn pre_upgrade() -> Result<Vec<u8>, sp_runtime::TryRuntimeError> {
+            ensure!(
+                LockedMessages::<T>::iter_keys().count() <= MAX_SETTLED_LOCKS as usize,
+                "bridge v4 migration: more locks than MAX_SETTLED_LOCKS"
+            );
+            let owners = LockedMessages::<T>::iter_values()
+                .map(|locked| locked.owner)
+                .collect::<sp_std::collections::btree_set::BTreeSet<_>>()
+                .into_iter()
+                .collect::<Vec<_>>();
+            Ok((Self::held_funds(&owners), owners).encode())
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn post_upgrade(state: Vec<u8>) -> Result<(), sp_runtime::TryRuntimeError> {
+            let (held_before, owners): (BalanceOf<T>, Vec<T::AccountId>) =
+                Decode::decode(&mut &state[..])
+                    .map_err(|_| "bridge v4 migration: undecodable pre-upgrade state")?;
+            ensure!(
+                Self::held_funds(&owners) == held_before,
+                "bridge v4 migration: pallet balance plus owner reserves changed"
+            );
+            let locked = LockedMessages::<T>::iter_values()
+                .fold(BalanceOf::<T>::zero(), |sum, locked| sum.saturating_add(locked.amount));
+            ensure!(
+                TotalLocked::<T>::get() == locked,
+                "bridge v4 migration: TotalLocked does not match LockedMessages"
+            );
+            ensure!(
+                T::Currency::free_balance(&Pallet::<T>::account_id()) >= ReleaseFund::<T>::get(),
+                "bridge v4 migration: pallet account does not cover ReleaseFund"
+            );
+            Ok(())
+        }
+    }
+
+    impl<T: Config> InnerMigrateV3ToV4<T> {
+        /// The pallet account's free balance plus the reserves of `owners`.
+        #[cfg(feature = "try-runtime")]
+        fn held_funds(owners: &[T::AccountId]) -> BalanceOf<T> {
+            use frame_support::traits::ReservableCurrency;
+            owners
+                .iter()
+                .fold(T::Currency::free_balance(&Pallet::<T>::account_id()), |sum, owner| {
+                    sum.saturating_add(T::Currency::reserved_balance(owner))
+                })
+        }
+    }
+
+    /// [`InnerMigrateV3ToV4`] gated on the on-chain storage version being 3.
+    pub type MigrateV3ToV4<T> = VersionedMigration<
+        3,
+        4,
+        InnerMigrateV3ToV4<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+pub mod v5 {
+    use super::*;
+
+    #[frame_support::storage_alias]
+    type Paused<T: Config> = StorageValue<Pallet<T>, bool, ValueQuery>;
+
+    /// Splits the single `Paused` flag into `LockPaused` and `ReleasePaused`, both taking its
+    /// value, so a bridge paused before the upgrade stays paused in both directions.
+    pub struct InnerMigrateV4ToV5<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV4ToV5<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let paused = Paused::<T>::take();
+            LockPaused::<T>::put(paused);
+            ReleasePaused::<T>::put(paused);
+            T::DbWeight::get().reads_writes(1, 3)
+        }
+    }
+
+    /// [`InnerMigrateV4ToV5`] gated on the on-chain storage version being 4.
+    pub type MigrateV4ToV5<T> = VersionedMigration<
+        4,
+        5,
+        InnerMigrateV4ToV5<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+pub mod v6 {
+    use super::*;
+
+    /// Seeds `ActiveLockCount` from the locks outstanding at the upgrade, so releasing them
+    /// frees their owners' slots under `MaxActiveLocksPerAccount`.
+    pub struct InnerMigrateV5ToV6<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV5ToV6<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let mut locks: u64 = 0;
+            for (_, locked) in LockedMessages::<T>::iter() {
+                locks += 1;
+                ActiveLockCount::<T>::mutate(&locked.owner, |count| {
+                    *count = count.saturating_add(1)
+                });
+            }
+            // per lock: the entry and its owner's count
+            T::DbWeight::get().reads_writes(locks * 2 + 1, locks)
+        }
+    }
+
+    /// [`InnerMigrateV5ToV6`] gated on the on-chain storage version being 5.
+    pub type MigrateV5ToV6<T> = VersionedMigration<
+        5,
+        6,
+        InnerMigrateV5ToV6<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
@@ -1,4 +1,9 @@
-use frame_support::{PalletId, derive_impl, pallet_prelude::ConstU32, parameter_types};
+use crate::SignaturePrefixScheme;
+use frame_support::{
+    PalletId, derive_impl, ord_parameter_types, pallet_prelude::ConstU32, parameter_types,
+    traits::EitherOfDiverse,
+};
+use frame_system::{EnsureRoot, EnsureSignedBy};
 use sp_runtime::BuildStorage;
 
 type Block = frame_system::mocking::MockBlock<Test>;
@@ -70,8 +75,19 @@ parameter_types! {
 
 parameter_types! {
     pub const BridgePalletId: PalletId = PalletId(*b"brdglock");
-    pub const RelayerThreshold: u32 = 0; // require 0 signature for mock
+    pub static RelayerThreshold: u32 = 0; // require 0 signature for mock
     pub const MaxSignatures: u32 = 10;   // max 10 signatures per release
+    pub const MaxMemoLen: u32 = 32;
+    pub const ProcessedRetention: u64 = 10;
+    pub const MaxReleaseBatch: u32 = 3;
+    pub static LockCooldown: u64 = 0;
+    pub static MaxActiveLocksPerAccount: u32 = 100;
+    pub static MaxReleaseAmount: u128 = 1_000;
+    pub static SignatureScheme: SignaturePrefixScheme = SignaturePrefixScheme::PersonalSign;
+}
+
+ord_parameter_types! {
+    pub const PrivilegedCouncil: AccountId = 7;
 }
 
 impl crate::Config for Test {
@@ -80,6 +96,16 @@ impl crate::Config for Test {
     type BridgePalletId = BridgePalletId;
     type RelayerThreshold = RelayerThreshold;
     type MaxSignatures = MaxSignatures;
+    type SignaturePrefixScheme = SignatureScheme;
+    type MaxMemoLen = MaxMemoLen;
+    type ProcessedRetention = ProcessedRetention;
+    type MaxReleaseBatch = MaxReleaseBatch;
+    type LockCooldown = LockCooldown;
+    type MaxActiveLocksPerAccount = MaxActiveLocksPerAccount;
+    type MaxReleaseAmount = MaxReleaseAmount;
+    type AdminOrigin = EnsureRoot<AccountId>;
+    type EmergencyOrigin =
+        EitherOfDiverse<EnsureRoot<AccountId>, EnsureSignedBy<PrivilegedCouncil, AccountId>>;
 }
 
 // Build genesis storage according to the mock runtime.
@@ -1,11 +1,71 @@
-use crate::{Error, Event, LockedInfo, MAX_RELAYERS, mock::*};
-use frame_support::{assert_noop, assert_ok};
-use sp_core::H160;
+use crate::{
+    Error, Event, LockedInfo, MAX_RELAYERS, ReleaseCheck, SignaturePrefixScheme, SignaturesOf,
+    mock::*,
+};
+use codec::{Decode, Encode};
+use frame_support::{assert_noop, assert_ok, traits::Hooks, weights::Weight};
+use sp_core::{H160, Pair, U256, ecdsa, keccak_256};
+use sp_runtime::traits::BadOrigin;
+
+/// Bounds raw signatures the way a decoded `release` call would.
+fn bounded(sigs: Vec<Vec<u8>>) -> SignaturesOf<Test> {
+    sigs.into_iter()
+        .map(|sig| sig.try_into().unwrap())
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap()
+}
 
 fn last_bridge_event() -> RuntimeEvent {
     System::events().pop().expect("expected at least one event").event
 }
 
+/// Expiry block the test signatures commit to; within `ProcessedRetention` of block 1.
+const VALID_UNTIL: u64 = 10;
+
+/// Digest a relayer signs for `message_id` and `valid_until` under `scheme`, computed
+/// independently of the pallet.
+fn digest_for(scheme: SignaturePrefixScheme, message_id: &[u8; 32], valid_until: u64) -> [u8; 32] {
+    let release = keccak_256(&[message_id.as_slice(), &valid_until.to_be_bytes()].concat());
+    match scheme {
+        SignaturePrefixScheme::PersonalSign =>
+            keccak_256(&[b"\x19Ethereum Signed Message:\n32".as_slice(), &release].concat()),
+        SignaturePrefixScheme::Raw => release,
+        SignaturePrefixScheme::Eip712 { domain_separator } => {
+            let type_hash = keccak_256(b"Release(bytes32 messageId,uint64 validUntil)");
+            let mut valid_until_word = [0u8; 32];
+            valid_until_word[24..].copy_from_slice(&valid_until.to_be_bytes());
+            let struct_hash =
+                keccak_256(&[type_hash.as_slice(), message_id, &valid_until_word].concat());
+            keccak_256(&[b"\x19\x01".as_slice(), &domain_separator, &struct_hash].concat())
+        },
+    }
+}
+
+/// Ethereum address controlled by `pair`.
+fn eth_address(pair: &ecdsa::Pair) -> H160 {
+    let digest = [7u8; 32];
+    let sig = pair.sign_prehashed(&digest);
+    let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&sig.0, &digest).unwrap();
+    H160::from_slice(&keccak_256(&pubkey)[12..32])
+}
+
+/// Signs `message_id` valid until `VALID_UNTIL` with `pair` under `scheme`, returning the 65-byte
+/// r||s||v signature.
+fn sign_under(pair: &ecdsa::Pair, scheme: SignaturePrefixScheme, message_id: &[u8; 32]) -> Vec<u8> {
+    sign_until(pair, scheme, message_id, VALID_UNTIL)
+}
+
+/// Like `sign_under`, with an explicit expiry block.
+fn sign_until(
+    pair: &ecdsa::Pair,
+    scheme: SignaturePrefixScheme,
+    message_id: &[u8; 32],
+    valid_until: u64,
+) -> Vec<u8> {
+    pair.sign_prehashed(&digest_for(scheme, message_id, valid_until)).0.to_vec()
+}
+
 #[test]
 fn root_can_set_relayers_and_too_many_relayers_errors() {
     new_test_ext().execute_with(|| {
@@ -44,7 +104,14 @@ fn lock_creates_locked_message_and_emits_event() {
         let nonce: u64 = 7;
 
         // call lock
-        assert_ok!(Bridge::lock(RuntimeOrigin::signed(sender), amount, fee, eth_recipient, nonce));
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(sender),
+            amount,
+            fee,
+            eth_recipient,
+            nonce,
+            Default::default()
+        ));
 
         // event captured
         let ev = last_bridge_event();
@@ -56,6 +123,7 @@ fn lock_creates_locked_message_and_emits_event() {
                 stored_eth,
                 stored_nonce,
                 message_id,
+                _,
             )) => {
                 assert_eq!(who, sender);
                 assert_eq!(stored_amount, amount);
@@ -66,17 +134,18 @@ fn lock_creates_locked_message_and_emits_event() {
                 // storage must contain LockedMessages for that id
                 let maybe = Bridge::locked(message_id);
                 assert!(maybe.is_some());
-                let info: LockedInfo<u64, u128> = maybe.unwrap();
+                let info: LockedInfo<u64, u128, _> = maybe.unwrap();
                 assert_eq!(info.owner, sender);
                 assert_eq!(info.amount, amount);
                 assert_eq!(info.relayer_fee, fee);
                 assert_eq!(info.eth_recipient, eth_recipient);
                 assert_eq!(info.nonce, nonce);
 
-                // pallet account should have received total = amount + fee
+                // amount is reserved on the sender, only the fee moves to the pallet account
+                assert_eq!(Balances::reserved_balance(sender), amount);
                 let pallet_acct = Bridge::account_id();
                 let pallet_bal = Balances::free_balance(pallet_acct);
-                assert_eq!(pallet_bal, amount + fee);
+                assert_eq!(pallet_bal, fee);
             },
             other => panic!("unexpected event: {other:?}"),
         }
@@ -93,7 +162,14 @@ fn cannot_lock_if_insufficient_balance() {
         let nonce: u64 = 1;
 
         assert_noop!(
-            Bridge::lock(RuntimeOrigin::signed(sender), amount, fee, eth_recipient, nonce),
+            Bridge::lock(
+                RuntimeOrigin::signed(sender),
+                amount,
+                fee,
+                eth_recipient,
+                nonce,
+                Default::default()
+            ),
             Error::<Test>::InsufficientBalance
         );
     });
@@ -113,12 +189,19 @@ fn release_transfers_and_reimburses_relayer_and_prevents_replay() {
         let before_relayer = Balances::free_balance(relayer_submitter);
 
         // lock
-        assert_ok!(Bridge::lock(RuntimeOrigin::signed(locker), amount, fee, eth_recipient, nonce));
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(locker),
+            amount,
+            fee,
+            eth_recipient,
+            nonce,
+            Default::default()
+        ));
 
         // capture Locked event and message_id
         let ev = last_bridge_event();
         let message_id = match ev {
-            RuntimeEvent::Bridge(Event::Locked(_, _, _, _, _, id)) => id,
+            RuntimeEvent::Bridge(Event::Locked(_, _, _, _, _, id, _)) => id,
             other => panic!("expected Locked event, got {other:?}"),
         };
 
@@ -128,7 +211,8 @@ fn release_transfers_and_reimburses_relayer_and_prevents_replay() {
             message_id,
             locker, // recipient is locker in this test for simplicity
             amount,
-            vec![],
+            VALID_UNTIL,
+            bounded(vec![]),
         ));
 
         // Released event emitted
@@ -150,7 +234,8 @@ fn release_transfers_and_reimburses_relayer_and_prevents_replay() {
                 message_id,
                 locker,
                 amount,
-                vec![],
+                VALID_UNTIL,
+                bounded(vec![]),
             ),
             Error::<Test>::MessageAlreadyProcessed
         );
@@ -179,7 +264,14 @@ fn top_up_relayer_fund_and_emergency_withdraw_works_and_pause_blocks_ops() {
         // operations blocked: lock should fail
         let eth_recipient = H160::repeat_byte(0xDE);
         assert_noop!(
-            Bridge::lock(RuntimeOrigin::signed(depositor), 5u128, 0u128, eth_recipient, 0u64),
+            Bridge::lock(
+                RuntimeOrigin::signed(depositor),
+                5u128,
+                0u128,
+                eth_recipient,
+                0u64,
+                Default::default()
+            ),
             Error::<Test>::Paused
         );
         // unpause
@@ -187,3 +279,1415 @@ fn top_up_relayer_fund_and_emergency_withdraw_works_and_pause_blocks_ops() {
         assert!(!Bridge::is_paused());
     });
 }
+
+#[test]
+fn compute_message_id_matches_lock() {
+    new_test_ext().execute_with(|| {
+        let sender: u64 = 1;
+        let amount: u128 = 120;
+        let eth_recipient = H160::repeat_byte(0xAB);
+        let nonce: u64 = 42;
+
+        let predicted = Bridge::compute_message_id(&sender, amount, eth_recipient, nonce).unwrap();
+
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(sender),
+            amount,
+            5,
+            eth_recipient,
+            nonce,
+            Default::default()
+        ));
+        match last_bridge_event() {
+            RuntimeEvent::Bridge(Event::Locked(_, _, _, _, _, id, _)) => assert_eq!(id, predicted),
+            other => panic!("expected Locked event, got {other:?}"),
+        }
+
+        // Any change in the inputs yields a different id.
+        assert_ne!(
+            Bridge::compute_message_id(&sender, amount, eth_recipient, nonce + 1).unwrap(),
+            predicted
+        );
+    });
+}
+
+#[test]
+fn recovery_succeeds_only_under_matching_signature_scheme() {
+    new_test_ext().execute_with(|| {
+        let relayer = ecdsa::Pair::from_seed(&[1u8; 32]);
+        let relayer_address = eth_address(&relayer);
+        let message_id = [0x42u8; 32];
+        let schemes = [
+            SignaturePrefixScheme::PersonalSign,
+            SignaturePrefixScheme::Raw,
+            SignaturePrefixScheme::Eip712 { domain_separator: [0x99u8; 32] },
+        ];
+
+        for signed_with in schemes {
+            let sig = sign_under(&relayer, signed_with, &message_id);
+            for configured in schemes {
+                SignatureScheme::set(configured);
+                let recovered = Bridge::ecdsa_recover_h160(&sig, &message_id, VALID_UNTIL).ok();
+                assert_eq!(
+                    recovered == Some(relayer_address),
+                    signed_with == configured,
+                    "signed with {signed_with:?}, configured {configured:?}"
+                );
+            }
+        }
+    });
+}
+
+#[test]
+fn release_counts_signatures_under_configured_scheme() {
+    new_test_ext().execute_with(|| {
+        let relayer = ecdsa::Pair::from_seed(&[2u8; 32]);
+        assert_ok!(Bridge::set_relayers(RuntimeOrigin::root(), vec![eth_address(&relayer)]));
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+
+        SignatureScheme::set(SignaturePrefixScheme::Raw);
+        let message_id = [0x24u8; 32];
+        let sig = sign_under(&relayer, SignaturePrefixScheme::Raw, &message_id);
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            message_id,
+            1,
+            10,
+            VALID_UNTIL,
+            bounded(vec![sig])
+        ));
+        assert_eq!(
+            last_bridge_event(),
+            RuntimeEvent::Bridge(Event::Released(1, 10, message_id, 1))
+        );
+
+        // A personal-sign signature does not count while the raw scheme is configured.
+        let message_id = [0x25u8; 32];
+        let sig = sign_under(&relayer, SignaturePrefixScheme::PersonalSign, &message_id);
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            message_id,
+            1,
+            10,
+            VALID_UNTIL,
+            bounded(vec![sig])
+        ));
+        assert_eq!(
+            last_bridge_event(),
+            RuntimeEvent::Bridge(Event::Released(1, 10, message_id, 0))
+        );
+    });
+}
+
+#[test]
+fn lock_stores_memo_and_rejects_over_length_memo() {
+    new_test_ext().execute_with(|| {
+        let eth_recipient = H160::repeat_byte(0xEE);
+        let memo = b"order-1234".to_vec();
+
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            50,
+            0,
+            eth_recipient,
+            3,
+            memo.clone().try_into().unwrap()
+        ));
+        let message_id = match last_bridge_event() {
+            RuntimeEvent::Bridge(Event::Locked(_, _, _, _, _, id, emitted_memo)) => {
+                assert_eq!(emitted_memo.to_vec(), memo);
+                id
+            },
+            other => panic!("expected Locked event, got {other:?}"),
+        };
+        assert_eq!(Bridge::locked(message_id).unwrap().memo.to_vec(), memo);
+
+        // The bound applies when the call is decoded, before any of it is dispatched.
+        let encoded_lock = |memo: Vec<u8>| (0u8, 50u128, 0u128, eth_recipient, 4u64, memo).encode();
+        let max_len = MaxMemoLen::get() as usize;
+        assert!(crate::Call::<Test>::decode(&mut &encoded_lock(vec![0u8; max_len])[..]).is_ok());
+        assert!(
+            crate::Call::<Test>::decode(&mut &encoded_lock(vec![0u8; max_len + 1])[..]).is_err()
+        );
+    });
+}
+
+#[test]
+fn on_idle_prunes_processed_messages_past_retention() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+
+        let old_id = [0x31u8; 32];
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            old_id,
+            1,
+            10,
+            VALID_UNTIL,
+            bounded(vec![])
+        ));
+
+        System::set_block_number(15);
+        let recent_id = [0x32u8; 32];
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            recent_id,
+            1,
+            10,
+            20,
+            bounded(vec![])
+        ));
+
+        Bridge::on_idle(20, Weight::MAX);
+
+        assert_eq!(Bridge::processed(old_id), None);
+        assert_eq!(Bridge::processed(recent_id), Some(15));
+        // still inside the retention window, so replay protection holds
+        assert_noop!(
+            Bridge::release(RuntimeOrigin::signed(2), recent_id, 1, 10, 20, bounded(vec![])),
+            Error::<Test>::MessageAlreadyProcessed
+        );
+        // the pruned id's signatures expired before it could be pruned, so it stays unreplayable
+        assert_noop!(
+            Bridge::release(RuntimeOrigin::signed(2), old_id, 1, 10, VALID_UNTIL, bounded(vec![])),
+            Error::<Test>::MessageExpired
+        );
+    });
+}
+
+#[test]
+fn release_rejects_expired_and_overlong_validity() {
+    new_test_ext().execute_with(|| {
+        let relayer = ecdsa::Pair::from_seed(&[7u8; 32]);
+        assert_ok!(Bridge::set_relayers(RuntimeOrigin::root(), vec![eth_address(&relayer)]));
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        RelayerThreshold::set(1);
+        let scheme = SignaturePrefixScheme::PersonalSign;
+        let message_id = [0x33u8; 32];
+
+        // Further ahead than ProcessedRetention (10 blocks from block 1).
+        let too_long = sign_until(&relayer, scheme, &message_id, 12);
+        assert_eq!(Bridge::can_release(message_id, 10, 12), ReleaseCheck::ValidityTooLong);
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                1,
+                10,
+                12,
+                bounded(vec![too_long])
+            ),
+            Error::<Test>::ValidityTooLong
+        );
+
+        let sig = sign_under(&relayer, scheme, &message_id);
+        System::set_block_number(VALID_UNTIL + 1);
+        assert_eq!(Bridge::can_release(message_id, 10, VALID_UNTIL), ReleaseCheck::Expired);
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                1,
+                10,
+                VALID_UNTIL,
+                bounded(vec![sig.clone()])
+            ),
+            Error::<Test>::MessageExpired
+        );
+
+        // Valid through the expiry block itself, and the signature binds the expiry.
+        System::set_block_number(VALID_UNTIL);
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                1,
+                10,
+                VALID_UNTIL - 1,
+                bounded(vec![sig.clone()])
+            ),
+            Error::<Test>::ThresholdNotMet
+        );
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            message_id,
+            1,
+            10,
+            VALID_UNTIL,
+            bounded(vec![sig])
+        ));
+    });
+}
+
+#[test]
+fn release_tracks_per_relayer_sign_counts() {
+    new_test_ext().execute_with(|| {
+        let alice = ecdsa::Pair::from_seed(&[3u8; 32]);
+        let bob = ecdsa::Pair::from_seed(&[4u8; 32]);
+        let charlie = ecdsa::Pair::from_seed(&[5u8; 32]);
+        let (a, b, c) = (eth_address(&alice), eth_address(&bob), eth_address(&charlie));
+        assert_ok!(Bridge::set_relayers(RuntimeOrigin::root(), vec![a, b, c]));
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        let scheme = SignaturePrefixScheme::PersonalSign;
+
+        let first = [0x41u8; 32];
+        let sigs = vec![sign_under(&alice, scheme, &first), sign_under(&bob, scheme, &first)];
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            first,
+            1,
+            10,
+            VALID_UNTIL,
+            bounded(sigs)
+        ));
+
+        // A duplicated signature only counts once.
+        let second = [0x42u8; 32];
+        let sig = sign_under(&alice, scheme, &second);
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            second,
+            1,
+            10,
+            VALID_UNTIL,
+            bounded(vec![sig.clone(), sig])
+        ));
+
+        assert_eq!(Bridge::relayer_sign_count(a), 2);
+        assert_eq!(Bridge::relayer_sign_count(b), 1);
+        assert_eq!(Bridge::relayer_sign_count(c), 0);
+
+        let mut stats = Bridge::relayer_stats();
+        stats.sort();
+        let mut expected = vec![(a, 2), (b, 1)];
+        expected.sort();
+        assert_eq!(stats, expected);
+    });
+}
+
+#[test]
+fn locks_are_reserved_per_owner_and_released_from_that_reserve() {
+    new_test_ext().execute_with(|| {
+        let eth_recipient = H160::repeat_byte(0xDD);
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            300,
+            0,
+            eth_recipient,
+            1,
+            Default::default()
+        ));
+        let first = match last_bridge_event() {
+            RuntimeEvent::Bridge(Event::Locked(_, _, _, _, _, id, _)) => id,
+            other => panic!("expected Locked event, got {other:?}"),
+        };
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(2),
+            100,
+            0,
+            eth_recipient,
+            1,
+            Default::default()
+        ));
+
+        assert_eq!(Balances::reserved_balance(1), 300);
+        assert_eq!(Balances::reserved_balance(2), 100);
+        assert_eq!(Balances::free_balance(Bridge::account_id()), 0);
+        assert_eq!(Bridge::total_locked(), 400);
+
+        // The release only draws on the first owner's reserve.
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            first,
+            2,
+            300,
+            VALID_UNTIL,
+            bounded(vec![])
+        ));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::reserved_balance(2), 100);
+        assert_eq!(Balances::free_balance(2), 900 + 300);
+        assert!(Bridge::locked(first).is_none());
+        assert_eq!(Bridge::total_locked(), 100);
+    });
+}
+
+#[test]
+fn release_must_match_the_locked_amount() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            50,
+            0,
+            H160::zero(),
+            1,
+            Default::default()
+        ));
+        let message_id = match last_bridge_event() {
+            RuntimeEvent::Bridge(Event::Locked(_, _, _, _, _, id, _)) => id,
+            other => panic!("expected Locked event, got {other:?}"),
+        };
+
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                2,
+                51,
+                VALID_UNTIL,
+                bounded(vec![])
+            ),
+            Error::<Test>::InsufficientLockedAmount
+        );
+        // A partial release would leave a remainder no one could release after the id is
+        // marked processed.
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                2,
+                49,
+                VALID_UNTIL,
+                bounded(vec![])
+            ),
+            Error::<Test>::PartialRelease
+        );
+
+        // Releasing the full amount unreserves everything and clears the lock.
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            message_id,
+            1,
+            50,
+            VALID_UNTIL,
+            bounded(vec![])
+        ));
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1000);
+        assert!(Bridge::locked(message_id).is_none());
+    });
+}
+
+#[test]
+fn admin_can_refund_a_lock_to_its_owner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            50,
+            5,
+            H160::zero(),
+            1,
+            Default::default()
+        ));
+        let message_id = match last_bridge_event() {
+            RuntimeEvent::Bridge(Event::Locked(_, _, _, _, _, id, _)) => id,
+            other => panic!("expected Locked event, got {other:?}"),
+        };
+
+        assert_noop!(Bridge::refund_lock(RuntimeOrigin::signed(1), message_id), BadOrigin);
+        assert_noop!(
+            Bridge::refund_lock(RuntimeOrigin::root(), [0x99u8; 32]),
+            Error::<Test>::NoLockedEntry
+        );
+
+        assert_ok!(Bridge::refund_lock(RuntimeOrigin::root(), message_id));
+        assert_eq!(
+            last_bridge_event(),
+            RuntimeEvent::Bridge(Event::LockRefunded { message_id, owner: 1, amount: 50 })
+        );
+        // The amount is back in the owner's free balance; the relayer fee is not refunded.
+        assert_eq!(Balances::reserved_balance(1), 0);
+        assert_eq!(Balances::free_balance(1), 1000 - 5);
+        assert!(Bridge::locked(message_id).is_none());
+        assert_eq!(Bridge::total_locked(), 0);
+        assert_eq!(Bridge::active_lock_count(1), 0);
+
+        // The refunded id can no longer be released, from the lock or the pallet account.
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                2,
+                50,
+                VALID_UNTIL,
+                bounded(vec![])
+            ),
+            Error::<Test>::MessageAlreadyProcessed
+        );
+    });
+}
+
+#[test]
+fn emergency_origin_can_pause_but_not_administer() {
+    new_test_ext().execute_with(|| {
+        let council = PrivilegedCouncil::get();
+
+        assert_ok!(Bridge::set_paused(RuntimeOrigin::signed(council), true));
+        assert!(Bridge::is_paused());
+        assert_noop!(Bridge::set_paused(RuntimeOrigin::signed(1), false), BadOrigin);
+
+        // Pausing rights do not extend to the admin actions.
+        assert_noop!(
+            Bridge::set_relayers(RuntimeOrigin::signed(council), vec![H160::zero()]),
+            BadOrigin
+        );
+        assert_noop!(Bridge::emergency_withdraw(RuntimeOrigin::signed(council), 7, 1), BadOrigin);
+
+        assert_ok!(Bridge::set_paused(RuntimeOrigin::root(), false));
+        assert!(!Bridge::is_paused());
+    });
+}
+
+#[test]
+fn release_batch_skips_replays_and_pays_fresh_messages() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        let replayed = [0x51u8; 32];
+        let fresh = [0x52u8; 32];
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            replayed,
+            1,
+            10,
+            VALID_UNTIL,
+            bounded(vec![])
+        ));
+        let before = Balances::free_balance(1);
+
+        assert_ok!(Bridge::release_batch(
+            RuntimeOrigin::signed(2),
+            vec![
+                (replayed, 1, 10, VALID_UNTIL, bounded(vec![])),
+                (fresh, 1, 20, VALID_UNTIL, bounded(vec![])),
+            ],
+        ));
+
+        assert_eq!(Balances::free_balance(1), before + 20);
+        assert!(Bridge::processed(fresh).is_some());
+        System::assert_has_event(RuntimeEvent::Bridge(Event::Released(1, 20, fresh, 0)));
+        assert_eq!(
+            last_bridge_event(),
+            RuntimeEvent::Bridge(Event::BatchReleased { released: 1, skipped: 1 })
+        );
+    });
+}
+
+#[test]
+fn release_batch_rejects_more_than_max_items() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        let items = (0..=MaxReleaseBatch::get() as u8)
+            .map(|i| ([i; 32], 1, 1, VALID_UNTIL, bounded(vec![])))
+            .collect::<Vec<_>>();
+
+        assert_noop!(
+            Bridge::release_batch(RuntimeOrigin::signed(2), items),
+            Error::<Test>::BatchTooLarge
+        );
+    });
+}
+
+#[test]
+fn release_rejects_fewer_signatures_than_threshold_before_recovery() {
+    new_test_ext().execute_with(|| {
+        let relayer = ecdsa::Pair::from_seed(&[6u8; 32]);
+        assert_ok!(Bridge::set_relayers(RuntimeOrigin::root(), vec![eth_address(&relayer)]));
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        RelayerThreshold::set(2);
+
+        let message_id = [0x61u8; 32];
+        let sig = sign_under(&relayer, SignaturePrefixScheme::PersonalSign, &message_id);
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                1,
+                10,
+                VALID_UNTIL,
+                bounded(vec![sig])
+            ),
+            Error::<Test>::ThresholdNotMet
+        );
+
+        // Even garbage signatures pass the count check and only fail after recovery.
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                1,
+                10,
+                VALID_UNTIL,
+                bounded(vec![vec![0u8; 65]; 2])
+            ),
+            Error::<Test>::ThresholdNotMet
+        );
+    });
+}
+
+#[test]
+fn duplicated_signatures_are_recovered_once() {
+    new_test_ext().execute_with(|| {
+        let alice = ecdsa::Pair::from_seed(&[7u8; 32]);
+        let bob = ecdsa::Pair::from_seed(&[8u8; 32]);
+        assert_ok!(Bridge::set_relayers(
+            RuntimeOrigin::root(),
+            vec![eth_address(&alice), eth_address(&bob)]
+        ));
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        let scheme = SignaturePrefixScheme::PersonalSign;
+
+        let message_id = [0x71u8; 32];
+        let a = sign_under(&alice, scheme, &message_id);
+        let b = sign_under(&bob, scheme, &message_id);
+        let sigs = bounded(vec![a.clone(), a.clone(), vec![1u8; 64], b.clone(), a.clone()]);
+        assert_eq!(Bridge::distinct_signatures(&sigs), vec![a.as_slice(), b.as_slice()]);
+
+        RelayerThreshold::set(2);
+        assert_ok!(Bridge::release(RuntimeOrigin::signed(2), message_id, 1, 10, VALID_UNTIL, sigs));
+        System::assert_last_event(RuntimeEvent::Bridge(Event::Released(1, 10, message_id, 2)));
+    });
+}
+
+#[test]
+fn can_release_reports_each_blocking_condition() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            50,
+            0,
+            H160::zero(),
+            1,
+            Default::default()
+        ));
+        let locked = match last_bridge_event() {
+            RuntimeEvent::Bridge(Event::Locked(_, _, _, _, _, id, _)) => id,
+            other => panic!("expected Locked event, got {other:?}"),
+        };
+        let unlocked = [0x81u8; 32];
+
+        assert_eq!(Bridge::can_release(locked, 50, VALID_UNTIL), ReleaseCheck::Ok);
+        assert_eq!(Bridge::can_release(locked, 51, VALID_UNTIL), ReleaseCheck::InsufficientFunds);
+        assert_eq!(Bridge::can_release(locked, 49, VALID_UNTIL), ReleaseCheck::PartialRelease);
+        assert_eq!(Bridge::can_release(unlocked, 10, VALID_UNTIL), ReleaseCheck::NoLockedEntry);
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                unlocked,
+                1,
+                10,
+                VALID_UNTIL,
+                bounded(vec![])
+            ),
+            Error::<Test>::InsufficientLockedAmount
+        );
+
+        // Relayer fees and relayer fund top-ups do not pay for unlocked releases.
+        assert_ok!(Bridge::top_up_relayer_fund(RuntimeOrigin::signed(2), 100));
+        assert_eq!(Bridge::can_release(unlocked, 10, VALID_UNTIL), ReleaseCheck::NoLockedEntry);
+
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        assert_eq!(Bridge::can_release(unlocked, 10, VALID_UNTIL), ReleaseCheck::Ok);
+
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            locked,
+            1,
+            50,
+            VALID_UNTIL,
+            bounded(vec![])
+        ));
+        assert_eq!(Bridge::can_release(locked, 50, VALID_UNTIL), ReleaseCheck::AlreadyProcessed);
+    });
+}
+
+#[test]
+fn release_that_would_reap_pool_account_fails() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        let message_id = [0x82u8; 32];
+
+        assert_eq!(
+            Bridge::can_release(message_id, 100, VALID_UNTIL),
+            ReleaseCheck::WouldReapPoolAccount
+        );
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                1,
+                100,
+                VALID_UNTIL,
+                bounded(vec![])
+            ),
+            Error::<Test>::WouldReapPoolAccount
+        );
+
+        assert_eq!(Bridge::can_release(message_id, 99, VALID_UNTIL), ReleaseCheck::Ok);
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            message_id,
+            1,
+            99,
+            VALID_UNTIL,
+            bounded(vec![])
+        ));
+        assert_eq!(Balances::free_balance(Bridge::account_id()), 1);
+        assert_eq!(Bridge::release_fund(), 1);
+    });
+}
+
+#[test]
+fn unlocked_releases_draw_down_the_release_fund() {
+    new_test_ext().execute_with(|| {
+        // Relayer fees sit in the pallet account but are not release fund.
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            10,
+            50,
+            H160::zero(),
+            1,
+            Default::default()
+        ));
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 30));
+        assert_eq!(last_bridge_event(), RuntimeEvent::Bridge(Event::ReleaseFundToppedUp(30)));
+        assert_eq!(Balances::free_balance(Bridge::account_id()), 80);
+        assert_eq!(Bridge::release_fund(), 30);
+
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            [0x83u8; 32],
+            2,
+            20,
+            VALID_UNTIL,
+            bounded(vec![])
+        ));
+        assert_eq!(Bridge::release_fund(), 10);
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                [0x84u8; 32],
+                2,
+                20,
+                VALID_UNTIL,
+                bounded(vec![])
+            ),
+            Error::<Test>::InsufficientLockedAmount
+        );
+
+        // Withdrawing from the pallet account shrinks the fund to what is left.
+        assert_ok!(Bridge::emergency_withdraw(RuntimeOrigin::root(), 1, 55));
+        assert_eq!(Bridge::release_fund(), 5);
+    });
+}
+
+#[test]
+fn releases_above_max_release_amount_are_rejected() {
+    new_test_ext().execute_with(|| {
+        MaxReleaseAmount::set(60);
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            60,
+            0,
+            H160::zero(),
+            1,
+            Default::default()
+        ));
+        let message_id = match last_bridge_event() {
+            RuntimeEvent::Bridge(Event::Locked(_, _, _, _, _, id, _)) => id,
+            other => panic!("expected Locked event, got {other:?}"),
+        };
+
+        assert_eq!(Bridge::can_release(message_id, 61, VALID_UNTIL), ReleaseCheck::AmountTooLarge);
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                1,
+                61,
+                VALID_UNTIL,
+                bounded(vec![])
+            ),
+            Error::<Test>::ReleaseAmountTooLarge
+        );
+
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            message_id,
+            1,
+            60,
+            VALID_UNTIL,
+            bounded(vec![])
+        ));
+        System::assert_last_event(RuntimeEvent::Bridge(Event::Released(1, 60, message_id, 0)));
+    });
+}
+
+#[test]
+fn locks_above_max_release_amount_are_rejected() {
+    new_test_ext().execute_with(|| {
+        MaxReleaseAmount::set(60);
+        assert_noop!(
+            Bridge::lock(RuntimeOrigin::signed(1), 61, 0, H160::zero(), 1, Default::default()),
+            Error::<Test>::ReleaseAmountTooLarge
+        );
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            60,
+            0,
+            H160::zero(),
+            1,
+            Default::default()
+        ));
+        assert_eq!(Bridge::total_locked(), 60);
+    });
+}
+
+#[test]
+fn amounts_above_u128_are_rejected_instead_of_saturated() {
+    new_test_ext().execute_with(|| {
+        // pallet-balances cannot run on a wider Balance, so exercise the conversion directly with
+        // a 256-bit amount.
+        assert_eq!(Bridge::amount_to_u128(U256::from(u128::MAX)), Ok(u128::MAX));
+        assert_eq!(Bridge::amount_to_u128(U256::from(u128::MAX) + 1), Err(Error::<Test>::Overflow));
+        assert_eq!(Bridge::balance_to_u128(&u128::MAX), Ok(u128::MAX));
+    });
+}
+
+#[test]
+fn oversized_signature_inputs_fail_to_decode() {
+    new_test_ext().execute_with(|| {
+        let message_id = [0x91u8; 32];
+        let call = |signatures| {
+            RuntimeCall::Bridge(crate::Call::release {
+                message_id,
+                recipient: 1,
+                amount: 10,
+                signatures,
+            })
+        };
+        // Same call prefix, with the signatures encoded as plain vectors.
+        let encode_raw = |sigs: Vec<Vec<u8>>| {
+            let mut encoded = call(bounded(vec![])).encode();
+            encoded.truncate(encoded.len() - Vec::<Vec<u8>>::new().encode().len());
+            encoded.extend(sigs.encode());
+            encoded
+        };
+
+        let valid = encode_raw(vec![vec![0u8; 65]]);
+        assert_eq!(
+            RuntimeCall::decode(&mut &valid[..]).unwrap(),
+            call(bounded(vec![vec![0u8; 65]]))
+        );
+
+        let too_long = encode_raw(vec![vec![0u8; 66]]);
+        assert!(RuntimeCall::decode(&mut &too_long[..]).is_err());
+
+        let too_many = encode_raw(vec![vec![0u8; 65]; MaxSignatures::get() as usize + 1]);
+        assert!(RuntimeCall::decode(&mut &too_many[..]).is_err());
+    });
+}
+
+#[test]
+fn each_direction_can_be_paused_independently() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+
+        // Stop inbound releases while outbound locks keep flowing.
+        assert_ok!(Bridge::set_direction_paused(RuntimeOrigin::root(), false, true));
+        System::assert_last_event(RuntimeEvent::Bridge(Event::DirectionPausedSet {
+            lock: false,
+            release: true,
+        }));
+        assert!(!Bridge::is_paused());
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            10,
+            0,
+            H160::zero(),
+            1,
+            Default::default()
+        ));
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                [0xA1u8; 32],
+                1,
+                10,
+                VALID_UNTIL,
+                bounded(vec![])
+            ),
+            Error::<Test>::Paused
+        );
+        assert_noop!(
+            Bridge::release_batch(
+                RuntimeOrigin::signed(2),
+                vec![([0xA1u8; 32], 1, 10, VALID_UNTIL, bounded(vec![]))]
+            ),
+            Error::<Test>::Paused
+        );
+
+        // And the other way round.
+        assert_ok!(Bridge::set_direction_paused(RuntimeOrigin::root(), true, false));
+        assert_noop!(
+            Bridge::lock(RuntimeOrigin::signed(1), 10, 0, H160::zero(), 2, Default::default()),
+            Error::<Test>::Paused
+        );
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            [0xA1u8; 32],
+            1,
+            10,
+            VALID_UNTIL,
+            bounded(vec![])
+        ));
+
+        assert_noop!(
+            Bridge::set_direction_paused(RuntimeOrigin::signed(1), false, false),
+            BadOrigin
+        );
+
+        // `set_paused` still covers both directions.
+        assert_ok!(Bridge::set_paused(RuntimeOrigin::root(), true));
+        assert!(Bridge::lock_paused() && Bridge::release_paused());
+        assert_ok!(Bridge::set_paused(RuntimeOrigin::root(), false));
+        assert!(!Bridge::lock_paused() && !Bridge::release_paused());
+    });
+}
+
+#[test]
+fn v2_migration_gives_locks_made_before_memos_an_empty_memo() {
+    use crate::migrations::v2::{InnerMigrateV1ToV2, LockedInfoV1};
+    use frame_support::traits::UncheckedOnRuntimeUpgrade;
+
+    new_test_ext().execute_with(|| {
+        let message_id = [0x71u8; 32];
+        let eth_recipient = H160::repeat_byte(0xDD);
+        frame_support::storage::unhashed::put(
+            &crate::LockedMessages::<Test>::hashed_key_for(message_id),
+            &LockedInfoV1 {
+                owner: 1u64,
+                amount: 50u128,
+                relayer_fee: 2u128,
+                eth_recipient,
+                nonce: 7,
+            },
+        );
+        // The old layout does not decode as the current one.
+        assert!(crate::LockedMessages::<Test>::try_get(message_id).is_err());
+
+        InnerMigrateV1ToV2::<Test>::on_runtime_upgrade();
+
+        assert_eq!(
+            Bridge::locked(message_id),
+            Some(LockedInfo {
+                owner: 1,
+                amount: 50,
+                relayer_fee: 2,
+                eth_recipient,
+                nonce: 7,
+                memo: Default::default(),
+            })
+        );
+    });
+}
+
+#[test]
+fn v4_migration_credits_settled_locks_to_the_release_fund() {
+    use crate::migrations::v4::InnerMigrateV3ToV4;
+    use frame_support::traits::UncheckedOnRuntimeUpgrade;
+
+    new_test_ext().execute_with(|| {
+        // Before v4 locked funds sat in the pallet account.
+        assert_ok!(Balances::transfer_allow_death(
+            RuntimeOrigin::signed(2),
+            Bridge::account_id(),
+            81
+        ));
+        for (owner, id) in [(1u64, [0x91u8; 32]), (2, [0x92u8; 32])] {
+            crate::LockedMessages::<Test>::insert(
+                id,
+                LockedInfo {
+                    owner,
+                    amount: 40,
+                    relayer_fee: 0,
+                    eth_recipient: H160::zero(),
+                    nonce: 1,
+                    memo: Default::default(),
+                },
+            );
+        }
+        crate::TotalLocked::<Test>::put(80);
+
+        InnerMigrateV3ToV4::<Test>::on_runtime_upgrade();
+
+        assert_eq!(Bridge::locked([0x91u8; 32]), None);
+        assert_eq!(Bridge::locked([0x92u8; 32]), None);
+        assert_eq!(Bridge::total_locked(), 0);
+        assert_eq!(Bridge::release_fund(), 80);
+        assert_eq!(Balances::free_balance(Bridge::account_id()), 81);
+        assert_ok!(Bridge::do_try_state());
+
+        // An inbound release carries a Bridge.sol id that matches no lock; the fund pays it.
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            [0x93u8; 32],
+            2,
+            40,
+            VALID_UNTIL,
+            bounded(vec![])
+        ));
+        assert_eq!(Bridge::release_fund(), 40);
+        assert_eq!(Balances::free_balance(Bridge::account_id()), 41);
+    });
+}
+
+#[test]
+fn v5_migration_carries_the_paused_flag_into_both_directions() {
+    use crate::migrations::v5::InnerMigrateV4ToV5;
+    use frame_support::traits::UncheckedOnRuntimeUpgrade;
+
+    new_test_ext().execute_with(|| {
+        frame_support::storage::unhashed::put(
+            &frame_support::storage::storage_prefix(b"Bridge", b"Paused"),
+            &true,
+        );
+
+        InnerMigrateV4ToV5::<Test>::on_runtime_upgrade();
+
+        assert!(Bridge::lock_paused() && Bridge::release_paused());
+        assert!(!frame_support::storage::unhashed::exists(
+            &frame_support::storage::storage_prefix(b"Bridge", b"Paused")
+        ));
+    });
+}
+
+/// Collects this thread's log lines for `LOG_TARGET`, so parallel tests don't see each other's.
+struct CaptureLogger;
+
+std::thread_local! {
+    static CAPTURED: core::cell::RefCell<Vec<String>> = const { core::cell::RefCell::new(Vec::new()) };
+}
+
+impl log::Log for CaptureLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.target() == crate::LOG_TARGET
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            CAPTURED.with(|lines| lines.borrow_mut().push(record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn capture_logs(f: impl FnOnce()) -> Vec<String> {
+    static LOGGER: CaptureLogger = CaptureLogger;
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&LOGGER).expect("no other logger is installed in bridge tests");
+        log::set_max_level(log::LevelFilter::Debug);
+    });
+    CAPTURED.with(|lines| lines.borrow_mut().clear());
+    f();
+    CAPTURED.with(|lines| lines.take())
+}
+
+#[test]
+fn release_logs_signature_diagnostics() {
+    new_test_ext().execute_with(|| {
+        let alice = ecdsa::Pair::from_seed(&[7u8; 32]);
+        let bob = ecdsa::Pair::from_seed(&[8u8; 32]);
+        let mallory = ecdsa::Pair::from_seed(&[9u8; 32]);
+        assert_ok!(Bridge::set_relayers(
+            RuntimeOrigin::root(),
+            vec![eth_address(&alice), eth_address(&bob)]
+        ));
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        let scheme = SignaturePrefixScheme::PersonalSign;
+        RelayerThreshold::set(2);
+
+        let message_id = [0x72u8; 32];
+        let a = sign_under(&alice, scheme, &message_id);
+        let m = sign_under(&mallory, scheme, &message_id);
+        let sigs = bounded(vec![a.clone(), a.clone(), vec![1u8; 64], m, vec![0u8; 65]]);
+
+        let lines = capture_logs(|| {
+            assert_noop!(
+                Bridge::release(RuntimeOrigin::signed(2), message_id, 1, 10, VALID_UNTIL, sigs),
+                Error::<Test>::ThresholdNotMet
+            );
+        });
+        assert_eq!(lines.len(), 1);
+        for field in [
+            "received=5",
+            "malformed=1",
+            "duplicates=1",
+            "unrecoverable=1",
+            "recovered_relayers=1",
+            "threshold=2",
+        ] {
+            assert!(lines[0].contains(field), "missing {field} in {}", lines[0]);
+        }
+
+        let lines = capture_logs(|| {
+            assert_noop!(
+                Bridge::release(
+                    RuntimeOrigin::signed(2),
+                    message_id,
+                    1,
+                    10,
+                    VALID_UNTIL,
+                    bounded(vec![a])
+                ),
+                Error::<Test>::ThresholdNotMet
+            );
+        });
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("received=1 below threshold=2"), "{}", lines[0]);
+    });
+}
+
+#[test]
+fn byte_distinct_signatures_from_one_relayer_count_once() {
+    new_test_ext().execute_with(|| {
+        let alice = ecdsa::Pair::from_seed(&[7u8; 32]);
+        let bob = ecdsa::Pair::from_seed(&[8u8; 32]);
+        assert_ok!(Bridge::set_relayers(
+            RuntimeOrigin::root(),
+            vec![eth_address(&alice), eth_address(&bob)]
+        ));
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        let scheme = SignaturePrefixScheme::PersonalSign;
+
+        let message_id = [0x73u8; 32];
+        let a = sign_under(&alice, scheme, &message_id);
+        let b = sign_under(&bob, scheme, &message_id);
+        // Same signature with an Ethereum-style recovery id: distinct bytes, same signer.
+        let eth_style = |sig: &Vec<u8>| {
+            let mut sig = sig.clone();
+            sig[64] += 27;
+            sig
+        };
+        let (a_eth, b_eth) = (eth_style(&a), eth_style(&b));
+        assert_eq!(
+            Bridge::ecdsa_recover_h160(&a_eth, &message_id, VALID_UNTIL).unwrap(),
+            eth_address(&alice)
+        );
+        let sigs = vec![a, a_eth, b, b_eth];
+
+        // Four signatures recover, but to only two relayers.
+        RelayerThreshold::set(3);
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                1,
+                10,
+                VALID_UNTIL,
+                bounded(sigs.clone())
+            ),
+            Error::<Test>::ThresholdNotMet
+        );
+
+        RelayerThreshold::set(2);
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            message_id,
+            1,
+            10,
+            VALID_UNTIL,
+            bounded(sigs)
+        ));
+        System::assert_last_event(RuntimeEvent::Bridge(Event::Released(1, 10, message_id, 2)));
+        assert_eq!(Bridge::relayer_sign_count(eth_address(&alice)), 1);
+        assert_eq!(Bridge::relayer_sign_count(eth_address(&bob)), 1);
+    });
+}
+
+#[test]
+fn relayers_and_threshold_are_set_together() {
+    new_test_ext().execute_with(|| {
+        let alice = ecdsa::Pair::from_seed(&[7u8; 32]);
+        let bob = ecdsa::Pair::from_seed(&[8u8; 32]);
+        let relayers = vec![eth_address(&alice), eth_address(&bob)];
+        assert_ok!(Bridge::set_relayers(RuntimeOrigin::root(), vec![H160::repeat_byte(0x11)]));
+
+        for threshold in [0, 3] {
+            assert_noop!(
+                Bridge::set_relayers_and_threshold(
+                    RuntimeOrigin::root(),
+                    relayers.clone(),
+                    threshold
+                ),
+                Error::<Test>::InvalidConfiguration
+            );
+        }
+        assert_noop!(
+            Bridge::set_relayers_and_threshold(RuntimeOrigin::signed(1), relayers.clone(), 2),
+            BadOrigin
+        );
+        // Until set on-chain, the configured threshold applies.
+        assert_eq!(Bridge::threshold(), RelayerThreshold::get());
+
+        assert_ok!(Bridge::set_relayers_and_threshold(RuntimeOrigin::root(), relayers.clone(), 2));
+        assert_eq!(Bridge::relayers().into_inner(), relayers);
+        assert_eq!(Bridge::threshold(), 2);
+        let events: Vec<_> = System::events().into_iter().map(|r| r.event).collect();
+        assert!(events.ends_with(&[
+            RuntimeEvent::Bridge(Event::RelayersUpdated(relayers)),
+            RuntimeEvent::Bridge(Event::ThresholdUpdated(2)),
+        ]));
+
+        // The on-chain threshold is enforced: one signature of two no longer suffices.
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        let message_id = [0x74u8; 32];
+        let a = sign_under(&alice, SignaturePrefixScheme::PersonalSign, &message_id);
+        assert_noop!(
+            Bridge::release(
+                RuntimeOrigin::signed(2),
+                message_id,
+                1,
+                10,
+                VALID_UNTIL,
+                bounded(vec![a])
+            ),
+            Error::<Test>::ThresholdNotMet
+        );
+    });
+}
+
+#[test]
+fn locks_respect_the_per_account_cooldown() {
+    new_test_ext().execute_with(|| {
+        let eth_recipient = H160::repeat_byte(0xDD);
+        // Without a cooldown, back-to-back locks are fine and nothing is tracked.
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            10,
+            0,
+            eth_recipient,
+            1,
+            Default::default()
+        ));
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            10,
+            0,
+            eth_recipient,
+            2,
+            Default::default()
+        ));
+        assert_eq!(Bridge::last_lock_block(1), None);
+
+        LockCooldown::set(5);
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            10,
+            0,
+            eth_recipient,
+            3,
+            Default::default()
+        ));
+        assert_eq!(Bridge::last_lock_block(1), Some(1));
+
+        System::set_block_number(5);
+        assert_noop!(
+            Bridge::lock(RuntimeOrigin::signed(1), 10, 0, eth_recipient, 4, Default::default()),
+            Error::<Test>::LockCooldownActive
+        );
+        // The cooldown is per account.
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(2),
+            10,
+            0,
+            eth_recipient,
+            4,
+            Default::default()
+        ));
+
+        System::set_block_number(6);
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            10,
+            0,
+            eth_recipient,
+            4,
+            Default::default()
+        ));
+        assert_eq!(Bridge::last_lock_block(1), Some(6));
+    });
+}
+
+#[test]
+fn active_locks_are_capped_per_account() {
+    new_test_ext().execute_with(|| {
+        MaxActiveLocksPerAccount::set(2);
+        let eth_recipient = H160::repeat_byte(0xDD);
+        let mut ids = Vec::new();
+        for nonce in 1..=2 {
+            assert_ok!(Bridge::lock(
+                RuntimeOrigin::signed(1),
+                10,
+                0,
+                eth_recipient,
+                nonce,
+                Default::default()
+            ));
+            match last_bridge_event() {
+                RuntimeEvent::Bridge(Event::Locked(_, _, _, _, _, id, _)) => ids.push(id),
+                other => panic!("expected Locked event, got {other:?}"),
+            }
+        }
+        assert_eq!(Bridge::active_lock_count(1), 2);
+        assert_noop!(
+            Bridge::lock(RuntimeOrigin::signed(1), 10, 0, eth_recipient, 3, Default::default()),
+            Error::<Test>::TooManyActiveLocks
+        );
+        // Other accounts have their own allowance.
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(2),
+            10,
+            0,
+            eth_recipient,
+            3,
+            Default::default()
+        ));
+
+        // Releasing a lock frees a slot, as does refunding one.
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            ids[1],
+            2,
+            10,
+            VALID_UNTIL,
+            bounded(vec![])
+        ));
+        assert_eq!(Bridge::active_lock_count(1), 1);
+        assert_ok!(Bridge::refund_lock(RuntimeOrigin::root(), ids[0]));
+        assert_eq!(Bridge::active_lock_count(1), 0);
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            10,
+            0,
+            eth_recipient,
+            4,
+            Default::default()
+        ));
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            10,
+            0,
+            eth_recipient,
+            3,
+            Default::default()
+        ));
+        assert_eq!(Bridge::active_lock_count(1), 2);
+    });
+}
+
+#[test]
+fn v6_migration_counts_outstanding_locks_per_owner() {
+    use crate::migrations::v6::InnerMigrateV5ToV6;
+    use frame_support::traits::UncheckedOnRuntimeUpgrade;
+
+    new_test_ext().execute_with(|| {
+        let eth_recipient = H160::repeat_byte(0xDD);
+        for (who, nonce) in [(1, 1), (1, 2), (2, 3)] {
+            assert_ok!(Bridge::lock(
+                RuntimeOrigin::signed(who),
+                10,
+                0,
+                eth_recipient,
+                nonce,
+                Default::default()
+            ));
+        }
+        // Locks made before the upgrade were not counted.
+        let _ = crate::ActiveLockCount::<Test>::clear(u32::MAX, None);
+        assert_eq!(Bridge::active_lock_count(1), 0);
+
+        InnerMigrateV5ToV6::<Test>::on_runtime_upgrade();
+
+        assert_eq!(Bridge::active_lock_count(1), 2);
+        assert_eq!(Bridge::active_lock_count(2), 1);
+    });
+}
+
+#[test]
+fn try_state_catches_total_locked_drift() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(1),
+            50,
+            0,
+            H160::zero(),
+            1,
+            Default::default()
+        ));
+        assert_ok!(Bridge::lock(
+            RuntimeOrigin::signed(2),
+            30,
+            0,
+            H160::zero(),
+            1,
+            Default::default()
+        ));
+        assert_ok!(Bridge::do_try_state());
+
+        crate::TotalLocked::<Test>::put(79);
+        assert!(Bridge::do_try_state().is_err());
+
+        crate::TotalLocked::<Test>::put(80);
+        assert_ok!(Bridge::do_try_state());
+    });
+}
+
+#[test]
+fn verify_release_signatures_counts_what_release_counts() {
+    new_test_ext().execute_with(|| {
+        let alice = ecdsa::Pair::from_seed(&[3u8; 32]);
+        let bob = ecdsa::Pair::from_seed(&[4u8; 32]);
+        let charlie = ecdsa::Pair::from_seed(&[5u8; 32]);
+        let outsider = ecdsa::Pair::from_seed(&[6u8; 32]);
+        let (a, b, c) = (eth_address(&alice), eth_address(&bob), eth_address(&charlie));
+        assert_ok!(Bridge::set_relayers_and_threshold(RuntimeOrigin::root(), vec![a, b, c], 2));
+        assert_ok!(Bridge::top_up_release_fund(RuntimeOrigin::signed(2), 100));
+        let scheme = SignaturePrefixScheme::PersonalSign;
+        let message_id = [0x61u8; 32];
+
+        // One signature from alice alone does not meet the threshold.
+        let alice_sig = sign_under(&alice, scheme, &message_id);
+        assert_eq!(
+            Bridge::verify_release_signatures(message_id, VALID_UNTIL, vec![alice_sig.clone()]),
+            1
+        );
+
+        // Duplicates, outsiders and malformed signatures do not count.
+        let sigs = vec![
+            alice_sig.clone(),
+            alice_sig,
+            sign_under(&outsider, scheme, &message_id),
+            vec![0u8; 64],
+            sign_under(&bob, scheme, &message_id),
+        ];
+        let counted = Bridge::verify_release_signatures(message_id, VALID_UNTIL, sigs.clone());
+        assert_eq!(counted, 2);
+        // Checking is read-only.
+        assert_eq!(Bridge::relayer_sign_count(a), 0);
+
+        assert_ok!(Bridge::release(
+            RuntimeOrigin::signed(2),
+            message_id,
+            1,
+            10,
+            VALID_UNTIL,
+            bounded(sigs)
+        ));
+        assert_eq!(
+            last_bridge_event(),
+            RuntimeEvent::Bridge(Event::Released(1, 10, message_id, counted))
+        );
+    });
+}
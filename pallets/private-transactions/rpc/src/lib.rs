@@ -0,0 +1,109 @@
+//! RPC interface for the confidential transactions pallet.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Codec;
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::error::{ErrorObject, ErrorObjectOwned},
+};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::H256;
+use sp_runtime::traits::Block as BlockT;
+
+pub use pallet_private_transactions_runtime_api::{
+    ConfidentialTransactionsApi as ConfidentialTransactionsRuntimeApi, TreeInfo,
+};
+
+/// Error code returned when the runtime API call fails.
+const RUNTIME_ERROR: i32 = 1;
+
+#[rpc(client, server)]
+pub trait ConfidentialTransactionsApi<BlockHash, AccountId, Balance> {
+    /// Returns the Merkle root the tree would have after inserting `leaf` next, or `None` if the
+    /// tree is full.
+    #[method(name = "confidential_simulateInsert")]
+    fn simulate_insert(&self, leaf: H256, at: Option<BlockHash>) -> RpcResult<Option<H256>>;
+
+    /// Returns the leaf indices of the deposits made by `who`, oldest first.
+    #[method(name = "confidential_depositsOf")]
+    fn deposits_of(&self, who: AccountId, at: Option<BlockHash>) -> RpcResult<Vec<u64>>;
+
+    /// Returns whether `root` is the current root or one of the retained recent roots.
+    #[method(name = "confidential_isKnownRoot")]
+    fn is_known_root(&self, root: H256, at: Option<BlockHash>) -> RpcResult<bool>;
+
+    /// Returns the free balance of the sovereign account backing the shielded pool.
+    #[method(name = "confidential_poolBalance")]
+    fn pool_balance(&self, at: Option<BlockHash>) -> RpcResult<Balance>;
+
+    /// Returns the commitment tree's depth, next leaf index and current root.
+    #[method(name = "confidential_treeInfo")]
+    fn tree_info(&self, at: Option<BlockHash>) -> RpcResult<TreeInfo>;
+}
+
+/// Provides RPC methods to query the confidential transactions pallet.
+pub struct ConfidentialTransactions<C, Block> {
+    client: Arc<C>,
+    _marker: PhantomData<Block>,
+}
+
+impl<C, Block> ConfidentialTransactions<C, Block> {
+    /// Creates a new instance of the confidential transactions RPC helper.
+    pub fn new(client: Arc<C>) -> Self {
+        Self { client, _marker: Default::default() }
+    }
+}
+
+impl<C, Block, AccountId, Balance>
+    ConfidentialTransactionsApiServer<<Block as BlockT>::Hash, AccountId, Balance>
+    for ConfidentialTransactions<C, Block>
+where
+    Block: BlockT,
+    C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+    C::Api: ConfidentialTransactionsRuntimeApi<Block, AccountId, Balance>,
+    AccountId: Codec + Send + Sync + 'static,
+    Balance: Codec + Send + Sync + 'static,
+{
+    fn simulate_insert(&self, leaf: H256, at: Option<Block::Hash>) -> RpcResult<Option<H256>> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.simulate_insert(at, leaf).map_err(runtime_error_into_rpc_err)
+    }
+
+    fn deposits_of(&self, who: AccountId, at: Option<Block::Hash>) -> RpcResult<Vec<u64>> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.deposits_of(at, who).map_err(runtime_error_into_rpc_err)
+    }
+
+    fn is_known_root(&self, root: H256, at: Option<Block::Hash>) -> RpcResult<bool> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.is_known_root(at, root).map_err(runtime_error_into_rpc_err)
+    }
+
+    fn pool_balance(&self, at: Option<Block::Hash>) -> RpcResult<Balance> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.pool_balance(at).map_err(runtime_error_into_rpc_err)
+    }
+
+    fn tree_info(&self, at: Option<Block::Hash>) -> RpcResult<TreeInfo> {
+        let api = self.client.runtime_api();
+        let at = at.unwrap_or_else(|| self.client.info().best_hash);
+
+        api.tree_info(at).map_err(runtime_error_into_rpc_err)
+    }
+}
+
+/// Converts a runtime trap into an RPC error.
+fn runtime_error_into_rpc_err(err: impl std::fmt::Debug) -> ErrorObjectOwned {
+    ErrorObject::owned(RUNTIME_ERROR, "Runtime error", Some(format!("{err:?}")))
+}
@@ -0,0 +1,36 @@
+//! Runtime API definition for the confidential transactions pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::Codec;
+use sp_core::H256;
+
+pub use pallet_private_transactions::TreeInfo;
+
+sp_api::decl_runtime_apis! {
+    /// Read-only queries against the confidential transactions pallet state.
+    pub trait ConfidentialTransactionsApi<AccountId, Balance>
+    where
+        AccountId: Codec,
+        Balance: Codec,
+    {
+        /// Returns the Merkle root the commitment tree would have if `leaf` were inserted at
+        /// the current `NextLeafIndex`, or `None` if the tree is full. No state is mutated.
+        fn simulate_insert(leaf: H256) -> Option<H256>;
+
+        /// Returns the leaf indices of the deposits made by `who`, oldest first.
+        fn deposits_of(who: AccountId) -> Vec<u64>;
+
+        /// Returns whether `root` is the current root or one of the retained recent roots.
+        fn is_known_root(root: H256) -> bool;
+
+        /// Returns the free balance of the pallet's sovereign account backing the shielded pool.
+        fn pool_balance() -> Balance;
+
+        /// Returns the commitment tree's depth, next leaf index and current root.
+        fn tree_info() -> TreeInfo;
+    }
+}
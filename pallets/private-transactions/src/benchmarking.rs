@@ -0,0 +1,55 @@
+//! Benchmarks for the confidential transactions pallet.
+
+use super::*;
+use ark_bn254::{Bn254, G1Affine, G2Affine};
+use ark_groth16::{Groth16, VerifyingKey};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_snark::SNARK;
+use ark_std::UniformRand;
+use frame_benchmarking::v2::*;
+use sp_std::vec::Vec;
+
+/// Serialized verifying key over random points, for a circuit with `inputs` public inputs.
+fn sample_vk(inputs: usize) -> Vec<u8> {
+    let mut rng = ark_std::test_rng();
+    let vk = VerifyingKey::<Bn254> {
+        alpha_g1: G1Affine::rand(&mut rng),
+        beta_g2: G2Affine::rand(&mut rng),
+        gamma_g2: G2Affine::rand(&mut rng),
+        delta_g2: G2Affine::rand(&mut rng),
+        gamma_abc_g1: (0..=inputs).map(|_| G1Affine::rand(&mut rng)).collect(),
+    };
+    let mut bytes = Vec::new();
+    vk.serialize_uncompressed(&mut bytes).expect("writing to a Vec cannot fail");
+    bytes
+}
+
+#[benchmarks]
+mod benchmarks {
+    use super::*;
+
+    /// Key handling of a verification without the cache: deserialize and prepare the raw key.
+    #[benchmark]
+    fn prepare_raw_key() {
+        let vk_bytes = sample_vk(6);
+
+        #[block]
+        {
+            let vk = VerifyingKey::<Bn254>::deserialize_uncompressed(&vk_bytes[..]).unwrap();
+            Groth16::<Bn254>::process_vk(&vk).unwrap();
+        }
+    }
+
+    /// Key handling of a verification with the cache: load the stored prepared key.
+    #[benchmark]
+    fn load_prepared_key() {
+        Pallet::<T>::cache_prepared_key(Circuit::Transfer, &sample_vk(6));
+
+        #[block]
+        {
+            Pallet::<T>::prepared_key(Circuit::Transfer, &[]).unwrap();
+        }
+    }
+
+    impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -26,7 +26,15 @@
 //! 1. Convert the native data type into a field element (`ark_bls12_381::Fr`).
 //! 2. Serialize the field element into bytes (`Vec<u8>`) using `ark_serialize::CanonicalSerialize`.
 //! 3. Pass the `Vec<Vec<u8>>` to the extrinsic. The order is critical and specified below.
-
+//!
+//! When `ProofDomainSeparation` is enabled, the pallet prepends the circuit id
+//! ([`Circuit::domain_tag`]) as an extra first public input before verifying. Callers do not pass
+//! it, but the circuits must expose it as their first public input, so a proof generated for one
+//! circuit can never satisfy the other circuit's verifier.
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+pub mod migrations;
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -117,19 +125,23 @@ pub mod pallet {
     };
     use frame_system::pallet_prelude::*;
     use sp_core::H256;
-    use sp_runtime::traits::AccountIdConversion;
+    use sp_io::hashing::blake2_256;
+    use sp_runtime::traits::{AccountIdConversion, Saturating};
     use sp_std::vec::Vec;
 
     // Arkworks ecosystem imports
     use ark_bn254::Fr;
     use ark_crypto_primitives::crh::TwoToOneCRHScheme;
     use ark_ff::PrimeField;
-    use ark_groth16::{Groth16, Proof, VerifyingKey};
-    use ark_serialize::CanonicalDeserialize;
+    use ark_groth16::{Groth16, PreparedVerifyingKey, Proof, VerifyingKey};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
     use ark_snark::SNARK;
 
     /// The current storage version.
-    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
+
+    /// Largest supported `TreeDepth`; leaf indices are `u64` and every insert walks the full depth.
+    pub const MAX_TREE_DEPTH: u32 = 32;
 
     type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
@@ -144,8 +156,90 @@ pub mod pallet {
         type Currency: ReservableCurrency<Self::AccountId>;
         #[pallet::constant]
         type PalletId: Get<PalletId>;
+        /// Depth of the commitment tree. Must not exceed [`MAX_TREE_DEPTH`].
         #[pallet::constant]
         type TreeDepth: Get<u32>;
+        /// How many blocks after becoming current a past Merkle root is still accepted by
+        /// `withdraw` and `transact`. The current root is always accepted.
+        #[pallet::constant]
+        type MaxRootAge: Get<BlockNumberFor<Self>>;
+        /// How many of the most recent Merkle roots are kept; older roots are evicted and no
+        /// longer accepted.
+        #[pallet::constant]
+        type RootHistorySize: Get<u32>;
+        /// Maximum number of deposit leaf indices recorded per depositor; the oldest is evicted
+        /// once it is full.
+        #[pallet::constant]
+        type MaxDepositsPerAccount: Get<u32>;
+        /// Smallest amount `deposit` accepts, so dust deposits cannot fill the commitment tree.
+        #[pallet::constant]
+        type MinDeposit: Get<BalanceOf<Self>>;
+        /// Most `public_inputs` any call accepts. Every input costs a field reduction during
+        /// verification, so longer vectors are rejected up front.
+        #[pallet::constant]
+        type MaxPublicInputs: Get<u32>;
+        /// Accept every proof without running the Groth16 verifier. Only for tests and local dev
+        /// chains; must be `false` on any live network.
+        #[pallet::constant]
+        type DevSkipProofVerification: Get<bool>;
+        /// Bind the [`Circuit`] id as the first public input of every verified proof. Requires
+        /// verification keys for circuits that expose the id.
+        #[pallet::constant]
+        type ProofDomainSeparation: Get<bool>;
+        /// Value of an absent leaf under the circuits' empty-tree convention. An absent node
+        /// higher up is the hash of two absent children, so each level has its own empty hash.
+        /// `None` keeps the legacy convention where every absent node is the zero hash.
+        #[pallet::constant]
+        type EmptyLeaf: Get<Option<H256>>;
+        /// Read proofs and verification keys in arkworks' compressed encoding instead of the
+        /// uncompressed one. Compressed proofs are half the size, but the stored keys must have
+        /// been serialized the same way.
+        #[pallet::constant]
+        type CompressedProofs: Get<bool>;
+    }
+
+    /// Rough ref-time of one Groth16 verification, charged by `dry_run_verify`.
+    const PROOF_VERIFICATION_REF_TIME: u64 = 20_000_000_000;
+
+    /// The circuits whose proofs this pallet verifies.
+    #[derive(
+        Encode,
+        Decode,
+        DecodeWithMemTracking,
+        Clone,
+        Copy,
+        PartialEq,
+        Eq,
+        RuntimeDebug,
+        TypeInfo,
+        MaxEncodedLen,
+    )]
+    pub enum Circuit {
+        Deposit,
+        Transfer,
+    }
+
+    impl Circuit {
+        /// Field element identifying this circuit in its public inputs.
+        pub fn domain_tag(self) -> Fr {
+            match self {
+                Circuit::Deposit => Fr::from(1u64),
+                Circuit::Transfer => Fr::from(2u64),
+            }
+        }
+    }
+
+    /// Commitment tree metadata a syncing wallet needs to bootstrap its local copy.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+    #[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+    pub struct TreeInfo {
+        /// Configured `TreeDepth`.
+        pub depth: u32,
+        /// Index the next inserted leaf will take.
+        pub next_leaf_index: u64,
+        /// Current Merkle root.
+        pub root: H256,
     }
 
     // --- Storage ---
@@ -159,10 +253,37 @@ pub mod pallet {
     #[pallet::unbounded]
     pub type TransferVerificationKey<T: Config> = StorageValue<_, Vec<u8>, OptionQuery>;
 
+    /// Serialized `PreparedVerifyingKey` of each circuit's current key, so verification skips
+    /// preparing the key from its raw bytes. Refreshed whenever a key is set; absent while the
+    /// key does not deserialize.
+    #[pallet::storage]
+    #[pallet::unbounded]
+    pub type PreparedVerificationKeys<T: Config> =
+        StorageMap<_, Twox64Concat, Circuit, Vec<u8>, OptionQuery>;
+
+    /// Current root of the commitment tree; the empty-tree root of `EmptyLeaf` until the first
+    /// deposit.
     #[pallet::storage]
     #[pallet::getter(fn merkle_root)]
     pub type MerkleRoot<T: Config> = StorageValue<_, H256, ValueQuery>;
 
+    /// `empty_nodes()` for the configured `EmptyLeaf` and `TreeDepth`, so inserts read the empty
+    /// node of every level instead of rehashing them. Unset while `EmptyLeaf` is `None`.
+    #[pallet::storage]
+    #[pallet::unbounded]
+    pub type EmptyNodes<T: Config> = StorageValue<_, Vec<H256>, OptionQuery>;
+
+    /// Roots in `RecentRoots`, mapped to the block they became current.
+    #[pallet::storage]
+    #[pallet::getter(fn root_block)]
+    pub type KnownRoots<T: Config> = StorageMap<_, Identity, H256, BlockNumberFor<T>, OptionQuery>;
+
+    /// The last `RootHistorySize` roots, oldest first.
+    #[pallet::storage]
+    #[pallet::getter(fn recent_roots)]
+    pub type RecentRoots<T: Config> =
+        StorageValue<_, BoundedVec<H256, T::RootHistorySize>, ValueQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn tree_nodes)]
     pub type TreeNodes<T: Config> = StorageMap<_, Blake2_128Concat, (u32, u64), H256, ValueQuery>;
@@ -171,10 +292,33 @@ pub mod pallet {
     #[pallet::getter(fn next_leaf_index)]
     pub type NextLeafIndex<T: Config> = StorageValue<_, u64, ValueQuery>;
 
+    /// Leaf indices created by each public depositor, so wallets can rediscover their notes.
+    /// Only the public deposit side is linked; transfers inside the pool are not indexed.
+    /// Holds the latest `MaxDepositsPerAccount` deposits of each account.
+    #[pallet::storage]
+    pub type Deposits<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        T::AccountId,
+        BoundedVec<u64, T::MaxDepositsPerAccount>,
+        ValueQuery,
+    >;
+
     #[pallet::storage]
     #[pallet::getter(fn nullifiers)]
     pub type Nullifiers<T: Config> = StorageMap<_, Blake2_128Concat, H256, bool, ValueQuery>;
 
+    /// Halts `deposit`, `transact` and `withdraw` while set.
+    #[pallet::storage]
+    #[pallet::getter(fn is_paused)]
+    pub type Paused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+    /// While the pool is paused, lets `withdraw` pay out fee-free to the recipient bound in the
+    /// proof, so users can evacuate. Cleared when the pool is unpaused.
+    #[pallet::storage]
+    #[pallet::getter(fn emergency_exit)]
+    pub type EmergencyExit<T: Config> = StorageValue<_, bool, ValueQuery>;
+
     #[pallet::genesis_config]
     pub struct GenesisConfig<T: Config> {
         pub deposit_vk: Vec<u8>,
@@ -195,8 +339,19 @@ pub mod pallet {
     #[pallet::genesis_build]
     impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
         fn build(&self) {
+            Pallet::<T>::ensure_tree_depth_supported();
+            Pallet::<T>::init_empty_tree().expect("the empty tree of `EmptyLeaf` hashes");
             DepositVerificationKey::<T>::put(&self.deposit_vk);
             TransferVerificationKey::<T>::put(&self.transfer_vk);
+            Pallet::<T>::cache_prepared_key(Circuit::Deposit, &self.deposit_vk);
+            Pallet::<T>::cache_prepared_key(Circuit::Transfer, &self.transfer_vk);
+        }
+    }
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+        fn integrity_test() {
+            Self::ensure_tree_depth_supported();
         }
     }
 
@@ -213,6 +368,15 @@ pub mod pallet {
         DepositVerificationKeySet,
         /// TransferVerificationKeySet
         TransferVerificationKeySet,
+        /// A root dropped out of `RecentRoots` and is no longer accepted. [root]
+        RootEvicted(H256),
+        /// The pool was paused or unpaused. [paused]
+        PausedSet(bool),
+        /// Emergency exit was enabled or disabled. [enabled]
+        EmergencyExitSet(bool),
+        /// A nullifier was marked spent by `withdraw` or `transact`, so wallets can watch for
+        /// their own notes being spent.
+        NullifierSpent { nullifier: H256, at: BlockNumberFor<T> },
     }
 
     #[pallet::error]
@@ -235,6 +399,20 @@ pub mod pallet {
         InvalidDepositAmount,
         /// The public inputs for the proof are malformed or do not match.
         InvalidPublicInputs,
+        /// The Merkle root is a past root that is older than `MaxRootAge`.
+        RootTooOld,
+        /// The commitment is the zero hash, which is reserved as the empty-node sentinel.
+        InvalidCommitment,
+        /// The deposit amount is below `MinDeposit`.
+        DepositTooSmall,
+        /// The pool is paused.
+        PoolPaused,
+        /// Emergency exit can only be enabled while the pool is paused.
+        EmergencyExitRequiresPause,
+        /// During an emergency exit the recipient must be the one bound in the proof.
+        RecipientNotBound,
+        /// Every leaf of the commitment tree is already taken.
+        TreeFull,
     }
 
     #[pallet::call]
@@ -251,7 +429,7 @@ pub mod pallet {
         /// - `amount`: The public amount of currency to deposit. Must match the amount in the
         ///   proof.
         #[pallet::call_index(0)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(5, 4))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(9, 8))]
         pub fn deposit(
             origin: OriginFor<T>,
             proof: Vec<u8>,
@@ -259,17 +437,21 @@ pub mod pallet {
             amount: BalanceOf<T>,
         ) -> DispatchResult {
             let who = ensure_signed(origin)?;
+            ensure!(!Self::is_paused(), Error::<T>::PoolPaused);
+            Self::ensure_public_inputs_arity(&public_inputs)?;
             ensure!(amount > 0u32.into(), Error::<T>::InvalidDepositAmount);
+            ensure!(amount >= T::MinDeposit::get(), Error::<T>::DepositTooSmall);
 
             let vk = Self::deposit_vk().ok_or(Error::<T>::DepositVerificationKeyNotSet)?;
 
             // Verify the deposit proof.
-            Self::verify_proof_internal(&vk, &proof, &public_inputs)?;
+            Self::verify_proof_internal(Circuit::Deposit, &vk, &proof, &public_inputs)?;
 
             // The commitment is the second public input from the proof.
             let commitment_bytes =
                 public_inputs.get(1).ok_or(Error::<T>::InvalidPublicInputs)?.clone();
             let commitment = H256::from_slice(&commitment_bytes);
+            ensure!(commitment != Self::empty_leaf(), Error::<T>::InvalidCommitment);
 
             // Transfer funds from the user to the pallet's sovereign account.
             T::Currency::transfer(
@@ -281,6 +463,7 @@ pub mod pallet {
 
             // Insert into our custom Merkle tree
             let leaf_index = Self::insert_leaf(commitment)?;
+            Self::index_deposit(&who, leaf_index);
 
             Self::deposit_event(Event::Deposit(who, amount, leaf_index));
             Ok(())
@@ -298,31 +481,46 @@ pub mod pallet {
         ///   - `[4]`: The transaction `fee`. (`Balance.as_bytes()`).
         /// - `recipient`: The public account ID to receive the funds.
         /// - `amount`: The public amount to withdraw. Must match the amount in the proof.
+        ///
+        /// While the pool is paused this only succeeds under `EmergencyExit`, with `[2]` equal to
+        /// [`Pallet::recipient_hash`] of `recipient`, and then it is fee-free.
         #[pallet::call_index(1)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(4, 4))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(8, 4))]
         pub fn withdraw(
             origin: OriginFor<T>,
             proof: Vec<u8>,
             public_inputs: Vec<Vec<u8>>,
             recipient: T::AccountId,
             amount: BalanceOf<T>,
-        ) -> DispatchResult {
+        ) -> DispatchResultWithPostInfo {
             let _who = ensure_signed(origin)?; // `who` pays the extrinsic fee
+            let emergency = Self::is_paused();
+            ensure!(!emergency || Self::emergency_exit(), Error::<T>::PoolPaused);
+            Self::ensure_public_inputs_arity(&public_inputs)?;
             let vk = Self::transfer_vk().ok_or(Error::<T>::TransferVerificationKeyNotSet)?;
 
-            // Verify the Merkle root from the public inputs matches the on-chain root.
+            // Verify the Merkle root from the public inputs is the on-chain root or a recent one.
             let merkle_root =
                 H256::from_slice(public_inputs.first().ok_or(Error::<T>::InvalidPublicInputs)?);
-            ensure!(merkle_root == Self::merkle_root(), Error::<T>::InvalidMerkleRoot);
+            Self::ensure_root_accepted(merkle_root)?;
 
             // Verify the withdrawal proof.
-            Self::verify_proof_internal(&vk, &proof, &public_inputs)?;
+            Self::verify_proof_internal(Circuit::Transfer, &vk, &proof, &public_inputs)?;
+
+            // An emergency exit may only pay the recipient the proof commits to.
+            if emergency {
+                ensure!(
+                    public_inputs.get(2).map(Vec::as_slice) ==
+                        Some(Self::recipient_hash(&recipient).as_bytes()),
+                    Error::<T>::RecipientNotBound
+                );
+            }
 
             // Check and use the nullifier from the public inputs.
             let nullifier =
                 H256::from_slice(public_inputs.get(1).ok_or(Error::<T>::InvalidPublicInputs)?);
             ensure!(!Self::nullifiers(nullifier), Error::<T>::NullifierAlreadyUsed);
-            Nullifiers::<T>::insert(nullifier, true);
+            Self::spend_nullifier(nullifier);
 
             // Transfer funds from the sovereign account to the recipient.
             T::Currency::transfer(
@@ -333,7 +531,7 @@ pub mod pallet {
             )?;
 
             Self::deposit_event(Event::Withdraw(recipient, amount));
-            Ok(())
+            Ok(if emergency { Pays::No } else { Pays::Yes }.into())
         }
 
         /// Perform a private transfer within the shielded pool.
@@ -347,20 +545,22 @@ pub mod pallet {
         ///   - `[3]`: The `commitment1` of the first new output note.
         ///   - `[4]`: The `commitment2` of the second new output note.
         #[pallet::call_index(2)]
-        #[pallet::weight(T::DbWeight::get().reads_writes(5, 7))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(10, 13))]
         pub fn transact(
             origin: OriginFor<T>,
             proof: Vec<u8>,
             public_inputs: Vec<Vec<u8>>,
         ) -> DispatchResult {
             let _who = ensure_signed(origin)?;
+            ensure!(!Self::is_paused(), Error::<T>::PoolPaused);
+            Self::ensure_public_inputs_arity(&public_inputs)?;
             let vk = Self::transfer_vk().ok_or(Error::<T>::TransferVerificationKeyNotSet)?;
 
             let merkle_root =
                 H256::from_slice(public_inputs.first().ok_or(Error::<T>::InvalidPublicInputs)?);
-            ensure!(merkle_root == Self::merkle_root(), Error::<T>::InvalidMerkleRoot);
+            Self::ensure_root_accepted(merkle_root)?;
 
-            Self::verify_proof_internal(&vk, &proof, &public_inputs)?;
+            Self::verify_proof_internal(Circuit::Transfer, &vk, &proof, &public_inputs)?;
 
             // Process nullifiers (inputs to the transaction)
             let nullifier1 =
@@ -369,14 +569,19 @@ pub mod pallet {
                 H256::from_slice(public_inputs.get(2).ok_or(Error::<T>::InvalidPublicInputs)?);
             ensure!(!Self::nullifiers(nullifier1), Error::<T>::NullifierAlreadyUsed);
             ensure!(!Self::nullifiers(nullifier2), Error::<T>::NullifierAlreadyUsed);
-            Nullifiers::<T>::insert(nullifier1, true);
-            Nullifiers::<T>::insert(nullifier2, true);
+            Self::spend_nullifier(nullifier1);
+            Self::spend_nullifier(nullifier2);
 
             // Process new commitments (outputs of the transaction)
             let commitment1 =
                 H256::from_slice(public_inputs.get(3).ok_or(Error::<T>::InvalidPublicInputs)?);
             let commitment2 =
                 H256::from_slice(public_inputs.get(4).ok_or(Error::<T>::InvalidPublicInputs)?);
+            let empty_leaf = Self::empty_leaf();
+            ensure!(
+                commitment1 != empty_leaf && commitment2 != empty_leaf,
+                Error::<T>::InvalidCommitment
+            );
             Self::insert_leaf(commitment1)?;
             Self::insert_leaf(commitment2)?;
 
@@ -390,6 +595,7 @@ pub mod pallet {
             ensure_root(origin)?; // Only root can call this
 
             DepositVerificationKey::<T>::put(&vk);
+            Self::cache_prepared_key(Circuit::Deposit, &vk);
             Self::deposit_event(Event::DepositVerificationKeySet);
             Ok(())
         }
@@ -400,30 +606,132 @@ pub mod pallet {
             ensure_root(origin)?; // Only root can call this
 
             TransferVerificationKey::<T>::put(&vk);
+            Self::cache_prepared_key(Circuit::Transfer, &vk);
             Self::deposit_event(Event::TransferVerificationKeySet);
             Ok(())
         }
+
+        /// Verify `proof` against the current key for `circuit` without touching the tree or
+        /// nullifiers, so wallets can check a proof before submitting the real transaction.
+        ///
+        /// The caller pays the normal transaction fee, weighted for a full verification.
+        #[pallet::call_index(5)]
+        #[pallet::weight(
+            T::DbWeight::get().reads(2).saturating_add(Weight::from_parts(PROOF_VERIFICATION_REF_TIME, 0))
+        )]
+        pub fn dry_run_verify(
+            origin: OriginFor<T>,
+            circuit: Circuit,
+            proof: Vec<u8>,
+            public_inputs: Vec<Vec<u8>>,
+        ) -> DispatchResult {
+            ensure_signed(origin)?;
+            Self::ensure_public_inputs_arity(&public_inputs)?;
+            let vk = match circuit {
+                Circuit::Deposit =>
+                    Self::deposit_vk().ok_or(Error::<T>::DepositVerificationKeyNotSet)?,
+                Circuit::Transfer =>
+                    Self::transfer_vk().ok_or(Error::<T>::TransferVerificationKeyNotSet)?,
+            };
+            Self::verify_proof_internal(circuit, &vk, &proof, &public_inputs)
+        }
+
+        /// Pause or unpause the pool (root only). Unpausing also ends any emergency exit.
+        #[pallet::call_index(6)]
+        #[pallet::weight(T::DbWeight::get().writes(2))]
+        pub fn set_paused(origin: OriginFor<T>, paused: bool) -> DispatchResult {
+            ensure_root(origin)?;
+
+            Paused::<T>::put(paused);
+            if !paused {
+                EmergencyExit::<T>::kill();
+            }
+            Self::deposit_event(Event::PausedSet(paused));
+            Ok(())
+        }
+
+        /// Enable or disable emergency exit (root only). It can only be enabled while the pool
+        /// is paused, and lets `withdraw` drain notes to their bound recipients without fees
+        /// while deposits and transfers stay blocked.
+        #[pallet::call_index(7)]
+        #[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+        pub fn set_emergency_exit(origin: OriginFor<T>, enabled: bool) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(!enabled || Self::is_paused(), Error::<T>::EmergencyExitRequiresPause);
+
+            EmergencyExit::<T>::put(enabled);
+            Self::deposit_event(Event::EmergencyExitSet(enabled));
+            Ok(())
+        }
     }
 
     impl<T: Config> Pallet<T> {
+        /// Panics if the configured `TreeDepth` exceeds [`MAX_TREE_DEPTH`].
+        fn ensure_tree_depth_supported() {
+            let depth = T::TreeDepth::get();
+            assert!(
+                depth <= MAX_TREE_DEPTH,
+                "TreeDepth is {depth}, but at most {MAX_TREE_DEPTH} is supported"
+            );
+        }
+
         /// Get the sovereign account ID for this pallet.
         pub fn sovereign_account_id() -> T::AccountId {
             T::PalletId::get().into_account_truncating()
         }
 
+        /// Recipient hash a withdrawal proof must carry in public input `[2]` for an emergency
+        /// exit: `blake2_256` of the SCALE-encoded account id.
+        pub fn recipient_hash(who: &T::AccountId) -> H256 {
+            H256(blake2_256(&who.encode()))
+        }
+
+        /// Marks `nullifier` as used and emits `NullifierSpent`. Callers check it was unused.
+        fn spend_nullifier(nullifier: H256) {
+            Nullifiers::<T>::insert(nullifier, true);
+            let at = frame_system::Pallet::<T>::block_number();
+            Self::deposit_event(Event::NullifierSpent { nullifier, at });
+        }
+
         /// Inserts a new leaf into the Merkle tree and updates the root.
+        ///
+        /// Missing siblings read as the empty node of their level (see `EmptyLeaf`), so the empty
+        /// leaf is reserved and never accepted as a commitment.
         fn insert_leaf(leaf: H256) -> Result<u64, DispatchError> {
             let leaf_index = Self::next_leaf_index();
-            let tree_depth = T::TreeDepth::get();
+            let path = Self::path_to_root(leaf_index, leaf)?;
 
-            <TreeNodes<T>>::insert((tree_depth, leaf_index), leaf);
+            <TreeNodes<T>>::insert((T::TreeDepth::get(), leaf_index), leaf);
+            for &(depth, index, hash) in &path {
+                <TreeNodes<T>>::insert((depth, index), hash);
+            }
 
+            let root = path.last().map_or(leaf, |&(_, _, hash)| hash);
+            <MerkleRoot<T>>::put(root);
+            Self::record_root(root);
+            <NextLeafIndex<T>>::put(leaf_index + 1);
+
+            Ok(leaf_index)
+        }
+
+        /// The `(depth, index, hash)` of every node that changes when `leaf` is inserted at
+        /// `leaf_index`, from the leaf's parent up to the root. Shared by `insert_leaf` and
+        /// `simulate_insert`; fails with `TreeFull` past the last leaf.
+        fn path_to_root(
+            leaf_index: u64,
+            leaf: H256,
+        ) -> Result<Vec<(u32, u64, H256)>, DispatchError> {
+            let tree_depth = T::TreeDepth::get();
+            ensure!(leaf_index < 1u64 << tree_depth, Error::<T>::TreeFull);
+            let empty_nodes = Self::empty_nodes()?;
+
+            let mut path = Vec::with_capacity(tree_depth as usize);
             let mut current_index = leaf_index;
             let mut current_hash = leaf;
             for depth in (0..tree_depth).rev() {
                 let sibling_index =
                     if current_index % 2 == 0 { current_index + 1 } else { current_index - 1 };
-                let sibling_hash = Self::tree_nodes((depth + 1, sibling_index));
+                let sibling_hash = Self::node_or_empty(depth + 1, sibling_index, &empty_nodes);
 
                 let (left, right) = if current_index % 2 == 0 {
                     (current_hash, sibling_hash)
@@ -431,37 +739,221 @@ pub mod pallet {
                     (sibling_hash, current_hash)
                 };
 
-                let parent_hash =
-                    Blake2s::compress(&(), &left.to_fixed_bytes(), &right.to_fixed_bytes())
-                        .map(H256::from)
-                        .map_err(|_| Error::<T>::InvalidProof)?; // Should not happen
-
                 current_index /= 2;
-                current_hash = parent_hash;
-                <TreeNodes<T>>::insert((depth, current_index), current_hash);
+                current_hash = Self::hash_children(left, right)?;
+                path.push((depth, current_index, current_hash));
             }
+            Ok(path)
+        }
 
-            <MerkleRoot<T>>::put(current_hash);
-            <NextLeafIndex<T>>::put(leaf_index + 1);
+        /// Pushes `root` into `RecentRoots`, evicting the oldest root once the buffer is full.
+        fn record_root(root: H256) {
+            let mut roots = Self::recent_roots();
+            if roots.is_full() {
+                // `RootHistorySize` of zero keeps no history at all
+                if roots.is_empty() {
+                    return;
+                }
+                let evicted = roots.remove(0);
+                <KnownRoots<T>>::remove(evicted);
+                Self::deposit_event(Event::RootEvicted(evicted));
+            }
+            // cannot fail: a slot was freed above
+            let _ = roots.try_push(root);
+            <RecentRoots<T>>::put(roots);
+            <KnownRoots<T>>::insert(root, frame_system::Pallet::<T>::block_number());
+        }
 
-            Ok(leaf_index)
+        /// Whether `root` is the current root or still in `RecentRoots`.
+        pub fn is_known_root(root: H256) -> bool {
+            root == Self::merkle_root() || <KnownRoots<T>>::contains_key(root)
+        }
+
+        /// Depth, next leaf index and current root of the commitment tree.
+        pub fn tree_info() -> TreeInfo {
+            TreeInfo {
+                depth: T::TreeDepth::get(),
+                next_leaf_index: Self::next_leaf_index(),
+                root: Self::merkle_root(),
+            }
+        }
+
+        /// Free balance of the sovereign account backing the shielded pool.
+        pub fn pool_balance() -> BalanceOf<T> {
+            T::Currency::free_balance(&Self::sovereign_account_id())
+        }
+
+        /// Leaf indices of the latest deposits made by `who`, oldest first.
+        pub fn deposits_of(who: &T::AccountId) -> Vec<u64> {
+            Deposits::<T>::get(who).into_inner()
+        }
+
+        /// Appends `leaf_index` to `who`'s `Deposits`, evicting the oldest entry if it is full,
+        /// so a full index never makes `deposit` fail.
+        fn index_deposit(who: &T::AccountId, leaf_index: u64) {
+            Deposits::<T>::mutate(who, |deposits| {
+                if deposits.is_full() && !deposits.is_empty() {
+                    deposits.remove(0);
+                }
+                let _ = deposits.try_push(leaf_index);
+            });
+        }
+
+        /// Accepts the current root, or a past root that became current at most `MaxRootAge`
+        /// blocks ago.
+        fn ensure_root_accepted(root: H256) -> DispatchResult {
+            if root == Self::merkle_root() {
+                return Ok(());
+            }
+            let root_block = Self::root_block(root).ok_or(Error::<T>::InvalidMerkleRoot)?;
+            let age = frame_system::Pallet::<T>::block_number().saturating_sub(root_block);
+            ensure!(age <= T::MaxRootAge::get(), Error::<T>::RootTooOld);
+            Ok(())
+        }
+
+        /// Computes the root the tree would have if `leaf` were inserted at the current
+        /// `NextLeafIndex`, without mutating any storage. Fails with `TreeFull` once every leaf
+        /// is taken.
+        pub fn simulate_insert(leaf: H256) -> Result<H256, DispatchError> {
+            let path = Self::path_to_root(Self::next_leaf_index(), leaf)?;
+            Ok(path.last().map_or(leaf, |&(_, _, hash)| hash))
+        }
+
+        /// The empty node of every level, indexed like `TreeNodes` from the root (`0`) down to
+        /// the leaves (`TreeDepth`). The root entry is the root of an empty tree. Read from
+        /// `EmptyNodes` unless that was computed for another `EmptyLeaf` or `TreeDepth`.
+        pub fn empty_nodes() -> Result<Vec<H256>, DispatchError> {
+            let levels = T::TreeDepth::get() as usize + 1;
+            let Some(leaf) = T::EmptyLeaf::get() else {
+                return Ok(sp_std::vec![H256::zero(); levels]);
+            };
+            match EmptyNodes::<T>::get() {
+                Some(nodes) if nodes.len() == levels && nodes.last() == Some(&leaf) => Ok(nodes),
+                _ => Self::hash_empty_nodes(leaf, levels),
+            }
+        }
+
+        /// Caches `empty_nodes()` in `EmptyNodes` and, while no leaf has been inserted, sets
+        /// `MerkleRoot` to the empty-tree root. Does nothing while `EmptyLeaf` is `None`, whose
+        /// empty root is the default zero hash.
+        pub(crate) fn init_empty_tree() -> DispatchResult {
+            let Some(leaf) = T::EmptyLeaf::get() else {
+                return Ok(());
+            };
+            let nodes = Self::hash_empty_nodes(leaf, T::TreeDepth::get() as usize + 1)?;
+            if Self::next_leaf_index() == 0 {
+                <MerkleRoot<T>>::put(nodes[0]);
+            }
+            EmptyNodes::<T>::put(nodes);
+            Ok(())
+        }
+
+        /// Hashes `leaf` up `levels` levels into the empty node of each level.
+        fn hash_empty_nodes(leaf: H256, levels: usize) -> Result<Vec<H256>, DispatchError> {
+            let mut nodes = sp_std::vec![leaf; levels];
+            for level in (0..levels - 1).rev() {
+                nodes[level] = Self::hash_children(nodes[level + 1], nodes[level + 1])?;
+            }
+            Ok(nodes)
+        }
+
+        /// The leaf value that stands for an absent leaf, reserved from use as a commitment.
+        fn empty_leaf() -> H256 {
+            T::EmptyLeaf::get().unwrap_or_default()
+        }
+
+        /// The stored node at `(depth, index)`, or the empty node of that level if absent.
+        fn node_or_empty(depth: u32, index: u64, empty_nodes: &[H256]) -> H256 {
+            TreeNodes::<T>::try_get((depth, index)).unwrap_or(empty_nodes[depth as usize])
+        }
+
+        /// Hashes two child nodes into their parent node.
+        fn hash_children(left: H256, right: H256) -> Result<H256, DispatchError> {
+            let parent_hash =
+                Blake2s::compress(&(), &left.to_fixed_bytes(), &right.to_fixed_bytes())
+                    .map(H256::from)
+                    .map_err(|_| Error::<T>::InvalidProof)?; // Should not happen
+            Ok(parent_hash)
+        }
+
+        /// Rejects more than `MaxPublicInputs` public inputs before any of them is parsed.
+        fn ensure_public_inputs_arity(public_inputs: &[Vec<u8>]) -> DispatchResult {
+            ensure!(
+                public_inputs.len() <= T::MaxPublicInputs::get() as usize,
+                Error::<T>::InvalidPublicInputs
+            );
+            Ok(())
+        }
+
+        /// Stores the prepared form of `vk_bytes` as `circuit`'s cached key, or drops the cached
+        /// key if `vk_bytes` is not a valid verifying key.
+        pub(crate) fn cache_prepared_key(circuit: Circuit, vk_bytes: &[u8]) {
+            let prepared = Self::deserialize_ark::<VerifyingKey<Bn254>>(vk_bytes)
+                .ok()
+                .and_then(|vk| Groth16::<Bn254>::process_vk(&vk).ok())
+                .and_then(|pvk| {
+                    let mut bytes = Vec::new();
+                    pvk.serialize_uncompressed(&mut bytes).ok().map(|_| bytes)
+                });
+            match prepared {
+                Some(bytes) => PreparedVerificationKeys::<T>::insert(circuit, bytes),
+                None => PreparedVerificationKeys::<T>::remove(circuit),
+            }
+        }
+
+        /// Reads a key or proof submitted in the encoding selected by `CompressedProofs`. The
+        /// prepared key cache is internal and always uncompressed.
+        fn deserialize_ark<A: CanonicalDeserialize>(bytes: &[u8]) -> Result<A, SerializationError> {
+            if T::CompressedProofs::get() {
+                A::deserialize_compressed(bytes)
+            } else {
+                A::deserialize_uncompressed(bytes)
+            }
+        }
+
+        /// `circuit`'s prepared key from the cache, or prepared from `vk_bytes` if not cached.
+        pub(crate) fn prepared_key(
+            circuit: Circuit,
+            vk_bytes: &[u8],
+        ) -> Result<PreparedVerifyingKey<Bn254>, Error<T>> {
+            // The cache is only ever written from a checked key, so skip re-validating it.
+            let cached = PreparedVerificationKeys::<T>::get(circuit).and_then(|bytes| {
+                PreparedVerifyingKey::<Bn254>::deserialize_uncompressed_unchecked(&bytes[..]).ok()
+            });
+            match cached {
+                Some(pvk) => Ok(pvk),
+                None => {
+                    let vk = Self::deserialize_ark::<VerifyingKey<Bn254>>(vk_bytes)
+                        .map_err(|_| Error::<T>::MalformedVerificationKey)?;
+                    Groth16::<Bn254>::process_vk(&vk)
+                        .map_err(|_| Error::<T>::MalformedVerificationKey)
+                },
+            }
         }
 
         /// Internal helper function to abstract proof verification.
         fn verify_proof_internal(
+            circuit: Circuit,
             vk_bytes: &[u8],
             proof_bytes: &[u8],
             public_inputs_bytes: &[Vec<u8>],
         ) -> DispatchResult {
-            let vk = VerifyingKey::<Bn254>::deserialize_uncompressed(vk_bytes)
-                .map_err(|_| Error::<T>::MalformedVerificationKey)?;
-            let proof = Proof::<Bn254>::deserialize_uncompressed(proof_bytes)
-                .map_err(|_| Error::<T>::MalformedProof)?;
-            let public_inputs_fr: Vec<Fr> =
-                public_inputs_bytes.iter().map(|b| Fr::from_be_bytes_mod_order(b)).collect();
+            if T::DevSkipProofVerification::get() {
+                return Ok(());
+            }
 
-            let verification_result = Groth16::<Bn254>::verify(&vk, &public_inputs_fr, &proof)
-                .map_err(|_| Error::<T>::InvalidProof)?;
+            let pvk = Self::prepared_key(circuit, vk_bytes)?;
+            let proof = Self::deserialize_ark::<Proof<Bn254>>(proof_bytes)
+                .map_err(|_| Error::<T>::MalformedProof)?;
+            let domain_tag = T::ProofDomainSeparation::get().then(|| circuit.domain_tag());
+            let public_inputs_fr: Vec<Fr> = domain_tag
+                .into_iter()
+                .chain(public_inputs_bytes.iter().map(|b| Fr::from_be_bytes_mod_order(b)))
+                .collect();
+
+            let verification_result =
+                Groth16::<Bn254>::verify_with_processed_vk(&pvk, &public_inputs_fr, &proof)
+                    .map_err(|_| Error::<T>::InvalidProof)?;
 
             ensure!(verification_result, Error::<T>::InvalidProof);
             Ok(())
@@ -0,0 +1,64 @@
+//! Storage migrations for the confidential transactions pallet.
+
+use crate::{Circuit, Config, DepositVerificationKey, Pallet, TransferVerificationKey};
+use frame_support::{
+    defensive, migrations::VersionedMigration, pallet_prelude::*, traits::UncheckedOnRuntimeUpgrade,
+};
+use sp_std::marker::PhantomData;
+
+pub mod v2 {
+    use super::*;
+
+    /// Fills `PreparedVerificationKeys` for the verification keys already on chain, so they
+    /// benefit from the cache without being set again.
+    pub struct InnerMigrateV1ToV2<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV1ToV2<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if let Some(vk) = DepositVerificationKey::<T>::get() {
+                Pallet::<T>::cache_prepared_key(Circuit::Deposit, &vk);
+            }
+            if let Some(vk) = TransferVerificationKey::<T>::get() {
+                Pallet::<T>::cache_prepared_key(Circuit::Transfer, &vk);
+            }
+            T::DbWeight::get().reads_writes(2, 2)
+        }
+    }
+
+    /// [`InnerMigrateV1ToV2`] gated on the on-chain storage version being 1.
+    pub type MigrateV1ToV2<T> = VersionedMigration<
+        1,
+        2,
+        InnerMigrateV1ToV2<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
+
+pub mod v3 {
+    use super::*;
+
+    /// Caches the empty node of every level in `EmptyNodes` and, if no deposit has been made
+    /// yet, sets `MerkleRoot` to the empty-tree root of the configured `EmptyLeaf`, as genesis
+    /// does from v3 on. A no-op while `EmptyLeaf` is `None`; hashing cannot fail in practice, and
+    /// if it did nothing would be written.
+    pub struct InnerMigrateV2ToV3<T>(PhantomData<T>);
+
+    impl<T: Config> UncheckedOnRuntimeUpgrade for InnerMigrateV2ToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            if Pallet::<T>::init_empty_tree().is_err() {
+                defensive!("confidential transactions v3 migration: failed to hash the empty tree");
+            }
+            T::DbWeight::get().reads_writes(1, 2)
+        }
+    }
+
+    /// [`InnerMigrateV2ToV3`] gated on the on-chain storage version being 2.
+    pub type MigrateV2ToV3<T> = VersionedMigration<
+        2,
+        3,
+        InnerMigrateV2ToV3<T>,
+        Pallet<T>,
+        <T as frame_system::Config>::DbWeight,
+    >;
+}
@@ -1,11 +1,19 @@
-use ark_bn254::{Bn254, G1Projective, G2Projective};
-use ark_groth16::{Proof, VerifyingKey};
+use crate::Circuit;
+use ark_bn254::{Bn254, Fr, G1Projective, G2Projective};
+use ark_ff::PrimeField;
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_relations::{
+    lc,
+    r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError, Variable},
+};
 use ark_serialize::CanonicalSerialize;
+use ark_snark::{CircuitSpecificSetupSNARK, SNARK};
 use ark_std::{
     UniformRand,
     rand::{SeedableRng, prelude::StdRng},
 };
 use frame_support::{PalletId, derive_impl, pallet_prelude::ConstU32, parameter_types};
+use sp_core::H256;
 use sp_runtime::BuildStorage;
 use std::{fs, sync::OnceLock};
 
@@ -73,7 +81,16 @@ impl pallet_balances::Config for Test {
 
 parameter_types! {
     pub const ConfidentialTransactionsPalletId: PalletId = PalletId(*b"xorionct");
-    pub const TreeDepth: u32 = 32;
+    pub static TreeDepth: u32 = 32;
+    pub const MaxRootAge: u64 = 10;
+    pub const RootHistorySize: u32 = 3;
+    pub const MaxDepositsPerAccount: u32 = 2;
+    pub const MinDeposit: u128 = 5;
+    pub const MaxPublicInputs: u32 = 8;
+    pub static DevSkipProofVerification: bool = false;
+    pub const ProofDomainSeparation: bool = true;
+    pub static EmptyLeaf: Option<H256> = None;
+    pub static CompressedProofs: bool = false;
 }
 
 impl crate::Config for Test {
@@ -81,6 +98,15 @@ impl crate::Config for Test {
     type Currency = Balances;
     type PalletId = ConfidentialTransactionsPalletId;
     type TreeDepth = TreeDepth;
+    type MaxRootAge = MaxRootAge;
+    type RootHistorySize = RootHistorySize;
+    type MaxDepositsPerAccount = MaxDepositsPerAccount;
+    type MinDeposit = MinDeposit;
+    type MaxPublicInputs = MaxPublicInputs;
+    type DevSkipProofVerification = DevSkipProofVerification;
+    type ProofDomainSeparation = ProofDomainSeparation;
+    type EmptyLeaf = EmptyLeaf;
+    type CompressedProofs = CompressedProofs;
 }
 
 /// Helper to create a valid, serialized but dummy verification key for testing.
@@ -114,6 +140,49 @@ fn create_dummy_vk(num_public_inputs: u32) -> (Vec<u8>, Vec<u8>) {
     (vk_bytes, proof_bytes)
 }
 
+/// Circuit whose public inputs are `circuit`'s domain tag followed by `inputs`, with the tag
+/// pinned to its constant value.
+struct TaggedCircuit {
+    circuit: Circuit,
+    inputs: Vec<Fr>,
+}
+
+impl ConstraintSynthesizer<Fr> for TaggedCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fr>) -> Result<(), SynthesisError> {
+        let tag = self.circuit.domain_tag();
+        let tag_var = cs.new_input_variable(|| Ok(tag))?;
+        cs.enforce_constraint(
+            lc!() + tag_var,
+            lc!() + Variable::One,
+            lc!() + (tag, Variable::One),
+        )?;
+        for value in self.inputs {
+            let var = cs.new_input_variable(|| Ok(value))?;
+            cs.enforce_constraint(lc!() + var, lc!() + Variable::One, lc!() + var)?;
+        }
+        Ok(())
+    }
+}
+
+/// Sets up `circuit` over `inputs` and proves it, returning the serialized verification key and
+/// a genuine proof that only verifies under `circuit`'s domain tag.
+pub fn prove_tagged(circuit: Circuit, inputs: &[Vec<u8>]) -> (Vec<u8>, Vec<u8>) {
+    let mut rng = StdRng::seed_from_u64(7);
+    let inputs: Vec<Fr> = inputs.iter().map(|b| Fr::from_be_bytes_mod_order(b)).collect();
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(
+        TaggedCircuit { circuit, inputs: inputs.clone() },
+        &mut rng,
+    )
+    .unwrap();
+    let proof = Groth16::<Bn254>::prove(&pk, TaggedCircuit { circuit, inputs }, &mut rng).unwrap();
+
+    let mut vk_bytes = Vec::new();
+    vk.serialize_uncompressed(&mut vk_bytes).unwrap();
+    let mut proof_bytes = Vec::new();
+    proof.serialize_uncompressed(&mut proof_bytes).unwrap();
+    (vk_bytes, proof_bytes)
+}
+
 pub static DEPOSIT_PROOF: OnceLock<Vec<u8>> = OnceLock::new();
 pub static TRANSFER_PROOF: OnceLock<Vec<u8>> = OnceLock::new();
 
@@ -131,8 +200,9 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 
     // Configure genesis for our pallet by creating and serializing
     // structurally valid (but dummy) verification keys.
-    let (deposit_vk, deposit_proof) = create_dummy_vk(2);
-    let (transfer_vk, transfer_proof) = create_dummy_vk(5);
+    // One extra public input for the circuit id prepended by `ProofDomainSeparation`.
+    let (deposit_vk, deposit_proof) = create_dummy_vk(3);
+    let (transfer_vk, transfer_proof) = create_dummy_vk(6);
     fs::write("vk_depo", hex::encode(&deposit_vk)).unwrap();
     fs::write("vk_trans", hex::encode(&transfer_vk)).unwrap();
     fs::write("proof_dep", hex::encode(&deposit_proof)).unwrap();
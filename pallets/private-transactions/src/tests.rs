@@ -1,5 +1,5 @@
-use crate::{Error, Pallet, mock::*};
-use frame_support::{assert_noop, assert_ok};
+use crate::{Circuit, Error, Pallet, PreparedVerificationKeys, TreeInfo, mock::*};
+use frame_support::{assert_noop, assert_ok, traits::Hooks};
 use sp_core::H256;
 
 #[test]
@@ -193,3 +193,829 @@ fn transact_works() {
         assert!(ConfidentialTransactions::nullifiers(nullifier2_hash));
     });
 }
+
+#[test]
+fn simulate_insert_matches_root_after_deposit() {
+    new_test_ext().execute_with(|| {
+        let amount = 100u128;
+        let first_commitment = H256::from_low_u64_be(123);
+        let second_commitment = H256::from_low_u64_be(124);
+
+        // Simulating against an empty tree must not touch storage.
+        let predicted = ConfidentialTransactions::simulate_insert(first_commitment).unwrap();
+        assert_eq!(ConfidentialTransactions::next_leaf_index(), 0);
+        assert_eq!(ConfidentialTransactions::merkle_root(), H256::default());
+
+        assert_ok!(ConfidentialTransactions::deposit(
+            RuntimeOrigin::signed(1),
+            DEPOSIT_PROOF.get().unwrap().clone(),
+            vec![amount.to_be_bytes().to_vec(), first_commitment.as_bytes().to_vec()],
+            amount
+        ));
+        assert_eq!(ConfidentialTransactions::merkle_root(), predicted);
+
+        // The prediction also holds once the tree already has siblings populated.
+        let predicted = ConfidentialTransactions::simulate_insert(second_commitment).unwrap();
+        assert_ok!(ConfidentialTransactions::deposit(
+            RuntimeOrigin::signed(1),
+            DEPOSIT_PROOF.get().unwrap().clone(),
+            vec![amount.to_be_bytes().to_vec(), second_commitment.as_bytes().to_vec()],
+            amount
+        ));
+        assert_eq!(ConfidentialTransactions::merkle_root(), predicted);
+    });
+}
+
+#[test]
+fn simulate_insert_and_deposit_fail_once_the_tree_is_full() {
+    TreeDepth::set(1);
+    new_test_ext().execute_with(|| {
+        for commitment in [1u64, 2] {
+            assert_ok!(ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(1),
+                DEPOSIT_PROOF.get().unwrap().clone(),
+                vec![
+                    10u128.to_be_bytes().to_vec(),
+                    H256::from_low_u64_be(commitment).as_bytes().to_vec()
+                ],
+                10
+            ));
+        }
+        let root = ConfidentialTransactions::merkle_root();
+
+        // A depth-1 tree holds two leaves; there is no root to predict for a third.
+        assert_noop!(
+            ConfidentialTransactions::simulate_insert(H256::from_low_u64_be(3)),
+            Error::<Test>::TreeFull
+        );
+        assert_noop!(
+            ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(2),
+                DEPOSIT_PROOF.get().unwrap().clone(),
+                vec![10u128.to_be_bytes().to_vec(), H256::from_low_u64_be(3).as_bytes().to_vec()],
+                10
+            ),
+            Error::<Test>::TreeFull
+        );
+        assert_eq!(ConfidentialTransactions::merkle_root(), root);
+    });
+}
+
+#[test]
+fn past_root_is_rejected_once_older_than_max_root_age() {
+    new_test_ext().execute_with(|| {
+        let withdraw_inputs = |root: H256, nullifier: u64| {
+            vec![
+                root.as_bytes().to_vec(),
+                H256::from_low_u64_be(nullifier).as_bytes().to_vec(),
+                H256::from_low_u64_be(2).as_bytes().to_vec(),
+                10u128.to_be_bytes().to_vec(),
+                0u128.to_be_bytes().to_vec(),
+            ]
+        };
+
+        // The first root becomes current at block 1 and is superseded at block 5.
+        assert_ok!(ConfidentialTransactions::deposit(
+            RuntimeOrigin::signed(1),
+            DEPOSIT_PROOF.get().unwrap().clone(),
+            vec![100u128.to_be_bytes().to_vec(), H256::from_low_u64_be(1).as_bytes().to_vec()],
+            100
+        ));
+        let old_root = ConfidentialTransactions::merkle_root();
+        System::set_block_number(5);
+        assert_ok!(ConfidentialTransactions::deposit(
+            RuntimeOrigin::signed(1),
+            DEPOSIT_PROOF.get().unwrap().clone(),
+            vec![100u128.to_be_bytes().to_vec(), H256::from_low_u64_be(2).as_bytes().to_vec()],
+            100
+        ));
+        assert_eq!(ConfidentialTransactions::root_block(old_root), Some(1));
+
+        // Still inside the window: 11 - 1 == MaxRootAge.
+        System::set_block_number(1 + MaxRootAge::get());
+        assert_ok!(ConfidentialTransactions::withdraw(
+            RuntimeOrigin::signed(1),
+            TRANSFER_PROOF.get().unwrap().clone(),
+            withdraw_inputs(old_root, 301),
+            2,
+            10
+        ));
+
+        System::set_block_number(2 + MaxRootAge::get());
+        assert_noop!(
+            ConfidentialTransactions::withdraw(
+                RuntimeOrigin::signed(1),
+                TRANSFER_PROOF.get().unwrap().clone(),
+                withdraw_inputs(old_root, 302),
+                2,
+                10
+            ),
+            Error::<Test>::RootTooOld
+        );
+
+        // The current root is accepted regardless of its age, unknown roots never are.
+        System::set_block_number(100);
+        assert_ok!(ConfidentialTransactions::withdraw(
+            RuntimeOrigin::signed(1),
+            TRANSFER_PROOF.get().unwrap().clone(),
+            withdraw_inputs(ConfidentialTransactions::merkle_root(), 303),
+            2,
+            10
+        ));
+        assert_noop!(
+            ConfidentialTransactions::withdraw(
+                RuntimeOrigin::signed(1),
+                TRANSFER_PROOF.get().unwrap().clone(),
+                withdraw_inputs(H256::repeat_byte(0xAB), 304),
+                2,
+                10
+            ),
+            Error::<Test>::InvalidMerkleRoot
+        );
+    });
+}
+
+#[test]
+fn deposits_are_indexed_per_depositor() {
+    new_test_ext().execute_with(|| {
+        let deposit = |who: u64, commitment: u64| {
+            ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(who),
+                DEPOSIT_PROOF.get().unwrap().clone(),
+                vec![
+                    10u128.to_be_bytes().to_vec(),
+                    H256::from_low_u64_be(commitment).as_bytes().to_vec(),
+                ],
+                10,
+            )
+        };
+
+        assert_ok!(deposit(1, 11));
+        assert_ok!(deposit(2, 21));
+        assert_ok!(deposit(1, 12));
+
+        assert_eq!(ConfidentialTransactions::deposits_of(&1), vec![0, 2]);
+        assert_eq!(ConfidentialTransactions::deposits_of(&2), vec![1]);
+        assert!(ConfidentialTransactions::deposits_of(&3).is_empty());
+
+        // Past MaxDepositsPerAccount the oldest entry is evicted; the deposit still goes through.
+        assert_ok!(deposit(1, 13));
+        assert_eq!(ConfidentialTransactions::deposits_of(&1), vec![2, 3]);
+        assert_eq!(ConfidentialTransactions::next_leaf_index(), 4);
+    });
+}
+
+#[test]
+fn proof_verification_is_only_skipped_when_dev_flag_is_set() {
+    new_test_ext().execute_with(|| {
+        let inputs =
+            vec![10u128.to_be_bytes().to_vec(), H256::from_low_u64_be(7).as_bytes().to_vec()];
+
+        // Without the flag a garbage proof is rejected by the verifier.
+        assert_noop!(
+            ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(1),
+                vec![0u8; 4],
+                inputs.clone(),
+                10
+            ),
+            Error::<Test>::MalformedProof
+        );
+
+        DevSkipProofVerification::set(true);
+        assert_ok!(ConfidentialTransactions::deposit(
+            RuntimeOrigin::signed(1),
+            vec![0u8; 4],
+            inputs,
+            10
+        ));
+        assert_eq!(ConfidentialTransactions::next_leaf_index(), 1);
+    });
+}
+
+#[test]
+fn deposit_rejects_zero_commitment() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(1),
+                DEPOSIT_PROOF.get().unwrap().clone(),
+                vec![100u128.to_be_bytes().to_vec(), H256::zero().as_bytes().to_vec()],
+                100
+            ),
+            Error::<Test>::InvalidCommitment
+        );
+    });
+}
+
+#[test]
+fn oldest_root_is_evicted_once_history_is_full() {
+    new_test_ext().execute_with(|| {
+        let mut roots = Vec::new();
+        for (who, commitment) in [(1, 1), (1, 2), (2, 3), (2, 4)] {
+            assert_ok!(ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(who),
+                DEPOSIT_PROOF.get().unwrap().clone(),
+                vec![
+                    10u128.to_be_bytes().to_vec(),
+                    H256::from_low_u64_be(commitment).as_bytes().to_vec(),
+                ],
+                10
+            ));
+            roots.push(ConfidentialTransactions::merkle_root());
+        }
+
+        System::assert_has_event(RuntimeEvent::ConfidentialTransactions(
+            crate::Event::RootEvicted(roots[0]),
+        ));
+        assert_eq!(ConfidentialTransactions::recent_roots().to_vec(), roots[1..].to_vec());
+        assert!(!ConfidentialTransactions::is_known_root(roots[0]));
+        assert!(roots[1..].iter().all(|root| ConfidentialTransactions::is_known_root(*root)));
+
+        assert_noop!(
+            ConfidentialTransactions::withdraw(
+                RuntimeOrigin::signed(1),
+                TRANSFER_PROOF.get().unwrap().clone(),
+                vec![
+                    roots[0].as_bytes().to_vec(),
+                    H256::from_low_u64_be(401).as_bytes().to_vec(),
+                    H256::from_low_u64_be(2).as_bytes().to_vec(),
+                    10u128.to_be_bytes().to_vec(),
+                    0u128.to_be_bytes().to_vec(),
+                ],
+                2,
+                10
+            ),
+            Error::<Test>::InvalidMerkleRoot
+        );
+    });
+}
+
+#[test]
+#[should_panic(expected = "TreeDepth is 33, but at most 32 is supported")]
+fn integrity_test_rejects_tree_depth_above_32() {
+    TreeDepth::set(33);
+    <ConfidentialTransactions as Hooks<u64>>::integrity_test();
+}
+
+#[test]
+fn transfer_proof_is_rejected_by_the_deposit_verifier() {
+    new_test_ext().execute_with(|| {
+        let root = ConfidentialTransactions::merkle_root();
+        let inputs: Vec<Vec<u8>> = [root]
+            .into_iter()
+            .chain((1..=4).map(H256::from_low_u64_be))
+            .map(|h| h.as_bytes().to_vec())
+            .collect();
+        let (vk, proof) = prove_tagged(Circuit::Transfer, &inputs);
+
+        // Misassign the transfer circuit's key to deposits as well.
+        assert_ok!(ConfidentialTransactions::set_transfer_verification_key(
+            RuntimeOrigin::root(),
+            vk.clone()
+        ));
+        assert_ok!(ConfidentialTransactions::set_deposit_verification_key(
+            RuntimeOrigin::root(),
+            vk
+        ));
+
+        assert_noop!(
+            ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(1),
+                proof.clone(),
+                inputs.clone(),
+                10
+            ),
+            Error::<Test>::InvalidProof
+        );
+        assert_ok!(ConfidentialTransactions::transact(RuntimeOrigin::signed(1), proof, inputs));
+    });
+}
+
+#[test]
+fn pool_balance_tracks_deposits_and_withdrawals() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(ConfidentialTransactions::pool_balance(), 0);
+
+        for (who, amount, commitment) in [(1, 100u128, 11), (2, 50, 12)] {
+            assert_ok!(ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(who),
+                DEPOSIT_PROOF.get().unwrap().clone(),
+                vec![
+                    amount.to_be_bytes().to_vec(),
+                    H256::from_low_u64_be(commitment).as_bytes().to_vec()
+                ],
+                amount
+            ));
+        }
+        assert_eq!(ConfidentialTransactions::pool_balance(), 150);
+
+        let withdraw_inputs = vec![
+            ConfidentialTransactions::merkle_root().as_bytes().to_vec(),
+            H256::from_low_u64_be(21).as_bytes().to_vec(),
+            H256::from_low_u64_be(2).as_bytes().to_vec(),
+            30u128.to_be_bytes().to_vec(),
+            0u64.to_be_bytes().to_vec(),
+        ];
+        assert_ok!(ConfidentialTransactions::withdraw(
+            RuntimeOrigin::signed(1),
+            TRANSFER_PROOF.get().unwrap().clone(),
+            withdraw_inputs,
+            2,
+            30
+        ));
+
+        assert_eq!(ConfidentialTransactions::pool_balance(), 120);
+        assert_eq!(
+            ConfidentialTransactions::pool_balance(),
+            Balances::free_balance(Pallet::<Test>::sovereign_account_id())
+        );
+    });
+}
+
+#[test]
+fn deposits_below_min_deposit_are_rejected() {
+    new_test_ext().execute_with(|| {
+        let deposit = |amount: u128| {
+            ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(1),
+                DEPOSIT_PROOF.get().unwrap().clone(),
+                vec![amount.to_be_bytes().to_vec(), H256::from_low_u64_be(9).as_bytes().to_vec()],
+                amount,
+            )
+        };
+
+        assert_noop!(deposit(MinDeposit::get() - 1), Error::<Test>::DepositTooSmall);
+        assert_ok!(deposit(MinDeposit::get()));
+        assert_eq!(ConfidentialTransactions::pool_balance(), MinDeposit::get());
+    });
+}
+
+#[test]
+fn dry_run_verify_checks_proofs_without_touching_state() {
+    new_test_ext().execute_with(|| {
+        let inputs: Vec<Vec<u8>> =
+            (1..=5).map(|i| H256::from_low_u64_be(i).as_bytes().to_vec()).collect();
+        let (vk, proof) = prove_tagged(Circuit::Transfer, &inputs);
+        assert_ok!(ConfidentialTransactions::set_transfer_verification_key(
+            RuntimeOrigin::root(),
+            vk
+        ));
+        let root = ConfidentialTransactions::merkle_root();
+
+        assert_ok!(ConfidentialTransactions::dry_run_verify(
+            RuntimeOrigin::signed(1),
+            Circuit::Transfer,
+            proof.clone(),
+            inputs.clone()
+        ));
+
+        let mut tampered = inputs.clone();
+        tampered[4] = H256::from_low_u64_be(6).as_bytes().to_vec();
+        assert_noop!(
+            ConfidentialTransactions::dry_run_verify(
+                RuntimeOrigin::signed(1),
+                Circuit::Transfer,
+                proof.clone(),
+                tampered
+            ),
+            Error::<Test>::InvalidProof
+        );
+        assert_noop!(
+            ConfidentialTransactions::dry_run_verify(
+                RuntimeOrigin::signed(1),
+                Circuit::Transfer,
+                vec![0u8; 4],
+                inputs
+            ),
+            Error::<Test>::MalformedProof
+        );
+
+        assert_eq!(ConfidentialTransactions::merkle_root(), root);
+        assert_eq!(ConfidentialTransactions::next_leaf_index(), 0);
+        assert!(!ConfidentialTransactions::nullifiers(H256::from_low_u64_be(2)));
+    });
+}
+
+#[test]
+fn tree_info_matches_on_chain_state() {
+    new_test_ext().execute_with(|| {
+        let empty_root = ConfidentialTransactions::merkle_root();
+        assert_eq!(
+            ConfidentialTransactions::tree_info(),
+            TreeInfo { depth: 32, next_leaf_index: 0, root: empty_root }
+        );
+
+        for commitment in 31..34 {
+            assert_ok!(ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(1),
+                DEPOSIT_PROOF.get().unwrap().clone(),
+                vec![
+                    10u128.to_be_bytes().to_vec(),
+                    H256::from_low_u64_be(commitment).as_bytes().to_vec()
+                ],
+                10
+            ));
+        }
+
+        let info = ConfidentialTransactions::tree_info();
+        assert_eq!(info.depth, TreeDepth::get());
+        assert_eq!(info.next_leaf_index, 3);
+        assert_eq!(info.next_leaf_index, ConfidentialTransactions::next_leaf_index());
+        assert_eq!(info.root, ConfidentialTransactions::merkle_root());
+        assert_ne!(info.root, empty_root);
+    });
+}
+
+#[test]
+fn oversized_public_inputs_are_rejected_before_verification() {
+    new_test_ext().execute_with(|| {
+        let too_many = vec![vec![1u8; 32]; MaxPublicInputs::get() as usize + 1];
+        // A garbage proof would fail with `MalformedProof` if it were ever deserialized.
+        let garbage = vec![0u8; 4];
+
+        assert_noop!(
+            ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(1),
+                garbage.clone(),
+                too_many.clone(),
+                10
+            ),
+            Error::<Test>::InvalidPublicInputs
+        );
+        assert_noop!(
+            ConfidentialTransactions::withdraw(
+                RuntimeOrigin::signed(1),
+                garbage.clone(),
+                too_many.clone(),
+                2,
+                10
+            ),
+            Error::<Test>::InvalidPublicInputs
+        );
+        assert_noop!(
+            ConfidentialTransactions::transact(
+                RuntimeOrigin::signed(1),
+                garbage.clone(),
+                too_many.clone()
+            ),
+            Error::<Test>::InvalidPublicInputs
+        );
+        assert_noop!(
+            ConfidentialTransactions::dry_run_verify(
+                RuntimeOrigin::signed(1),
+                Circuit::Transfer,
+                garbage,
+                too_many
+            ),
+            Error::<Test>::InvalidPublicInputs
+        );
+    });
+}
+
+#[test]
+fn prepared_key_cache_follows_key_rotation() {
+    new_test_ext().execute_with(|| {
+        let inputs: Vec<Vec<u8>> =
+            (1..=5).map(|i| H256::from_low_u64_be(i).as_bytes().to_vec()).collect();
+        let (old_vk, old_proof) = prove_tagged(Circuit::Transfer, &inputs);
+        let (new_vk, new_proof) = prove_tagged(Circuit::Transfer, &inputs[..4]);
+        let dry_run = |proof: &Vec<u8>, inputs: &[Vec<u8>]| {
+            ConfidentialTransactions::dry_run_verify(
+                RuntimeOrigin::signed(1),
+                Circuit::Transfer,
+                proof.clone(),
+                inputs.to_vec(),
+            )
+        };
+
+        assert_ok!(ConfidentialTransactions::set_transfer_verification_key(
+            RuntimeOrigin::root(),
+            old_vk
+        ));
+        let cached = PreparedVerificationKeys::<Test>::get(Circuit::Transfer).unwrap();
+        assert_ok!(dry_run(&old_proof, &inputs));
+
+        // Rotating the key refreshes the cache: only proofs for the new key verify.
+        assert_ok!(ConfidentialTransactions::set_transfer_verification_key(
+            RuntimeOrigin::root(),
+            new_vk
+        ));
+        assert_ne!(PreparedVerificationKeys::<Test>::get(Circuit::Transfer).unwrap(), cached);
+        assert_noop!(dry_run(&old_proof, &inputs), Error::<Test>::InvalidProof);
+        assert_ok!(dry_run(&new_proof, &inputs[..4]));
+
+        // A malformed key drops the cache rather than leaving the previous key in force.
+        assert_ok!(ConfidentialTransactions::set_transfer_verification_key(
+            RuntimeOrigin::root(),
+            vec![1u8; 8]
+        ));
+        assert!(!PreparedVerificationKeys::<Test>::contains_key(Circuit::Transfer));
+        assert_noop!(dry_run(&new_proof, &inputs[..4]), Error::<Test>::MalformedVerificationKey);
+    });
+}
+
+#[test]
+fn v2_migration_caches_keys_already_on_chain() {
+    use crate::migrations::v2::InnerMigrateV1ToV2;
+    use frame_support::traits::UncheckedOnRuntimeUpgrade;
+
+    new_test_ext().execute_with(|| {
+        let _ = PreparedVerificationKeys::<Test>::clear(u32::MAX, None);
+
+        InnerMigrateV1ToV2::<Test>::on_runtime_upgrade();
+
+        assert!(PreparedVerificationKeys::<Test>::contains_key(Circuit::Deposit));
+        assert!(PreparedVerificationKeys::<Test>::contains_key(Circuit::Transfer));
+        assert_ok!(ConfidentialTransactions::deposit(
+            RuntimeOrigin::signed(1),
+            DEPOSIT_PROOF.get().unwrap().clone(),
+            vec![10u128.to_be_bytes().to_vec(), H256::from_low_u64_be(41).as_bytes().to_vec()],
+            10
+        ));
+    });
+}
+
+#[test]
+fn emergency_exit_pays_bound_recipients_while_deposits_stay_blocked() {
+    use frame_support::dispatch::Pays;
+
+    new_test_ext().execute_with(|| {
+        let deposit_inputs =
+            vec![100u128.to_be_bytes().to_vec(), H256::from_low_u64_be(51).as_bytes().to_vec()];
+        assert_ok!(ConfidentialTransactions::deposit(
+            RuntimeOrigin::signed(1),
+            DEPOSIT_PROOF.get().unwrap().clone(),
+            deposit_inputs.clone(),
+            100
+        ));
+        let withdraw_inputs = |recipient_hash: H256| {
+            vec![
+                ConfidentialTransactions::merkle_root().as_bytes().to_vec(),
+                H256::from_low_u64_be(52).as_bytes().to_vec(),
+                recipient_hash.as_bytes().to_vec(),
+                100u128.to_be_bytes().to_vec(),
+                0u128.to_be_bytes().to_vec(),
+            ]
+        };
+        let withdraw = |inputs: Vec<Vec<u8>>| {
+            ConfidentialTransactions::withdraw(
+                RuntimeOrigin::signed(1),
+                TRANSFER_PROOF.get().unwrap().clone(),
+                inputs,
+                2,
+                100,
+            )
+        };
+        let bound = withdraw_inputs(Pallet::<Test>::recipient_hash(&2));
+
+        assert_ok!(ConfidentialTransactions::set_paused(RuntimeOrigin::root(), true));
+        assert_noop!(withdraw(bound.clone()), Error::<Test>::PoolPaused);
+
+        assert_ok!(ConfidentialTransactions::set_emergency_exit(RuntimeOrigin::root(), true));
+        assert_noop!(
+            ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(1),
+                DEPOSIT_PROOF.get().unwrap().clone(),
+                deposit_inputs,
+                100
+            ),
+            Error::<Test>::PoolPaused
+        );
+        assert_noop!(
+            ConfidentialTransactions::transact(
+                RuntimeOrigin::signed(1),
+                TRANSFER_PROOF.get().unwrap().clone(),
+                (0..5).map(|i| H256::from_low_u64_be(60 + i).as_bytes().to_vec()).collect(),
+            ),
+            Error::<Test>::PoolPaused
+        );
+        assert_noop!(
+            withdraw(withdraw_inputs(H256::from_low_u64_be(2))),
+            Error::<Test>::RecipientNotBound
+        );
+
+        let info = withdraw(bound).unwrap();
+        assert_eq!(info.pays_fee, Pays::No);
+        assert_eq!(Balances::free_balance(2), 1100);
+    });
+}
+
+#[test]
+fn emergency_exit_is_only_available_while_paused() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            ConfidentialTransactions::set_paused(RuntimeOrigin::signed(1), true),
+            sp_runtime::DispatchError::BadOrigin
+        );
+        assert_noop!(
+            ConfidentialTransactions::set_emergency_exit(RuntimeOrigin::root(), true),
+            Error::<Test>::EmergencyExitRequiresPause
+        );
+
+        assert_ok!(ConfidentialTransactions::set_paused(RuntimeOrigin::root(), true));
+        assert_ok!(ConfidentialTransactions::set_emergency_exit(RuntimeOrigin::root(), true));
+        assert!(ConfidentialTransactions::emergency_exit());
+
+        // Unpausing ends the emergency exit, so the next pause starts fully closed.
+        assert_ok!(ConfidentialTransactions::set_paused(RuntimeOrigin::root(), false));
+        assert!(!ConfidentialTransactions::emergency_exit());
+    });
+}
+
+#[test]
+fn configured_empty_leaf_hashes_up_each_empty_level() {
+    let hash = |left: H256, right: H256| {
+        H256(sp_io::hashing::blake2_256(&[left.as_bytes(), right.as_bytes()].concat()))
+    };
+
+    new_test_ext().execute_with(|| {
+        TreeDepth::set(3);
+        let empty_leaf = H256::repeat_byte(0xee);
+        EmptyLeaf::set(Some(empty_leaf));
+
+        // Reference empty tree: each level is the hash of two empty children.
+        let e2 = hash(empty_leaf, empty_leaf);
+        let e1 = hash(e2, e2);
+        let e0 = hash(e1, e1);
+        assert_eq!(Pallet::<Test>::empty_nodes().unwrap(), vec![e0, e1, e2, empty_leaf]);
+
+        let deposit = |who: u64, commitment: H256| {
+            ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(who),
+                DEPOSIT_PROOF.get().unwrap().clone(),
+                vec![10u128.to_be_bytes().to_vec(), commitment.as_bytes().to_vec()],
+                10,
+            )
+        };
+        let (first, second) = (H256::from_low_u64_be(71), H256::from_low_u64_be(72));
+
+        assert_ok!(deposit(1, first));
+        let root = hash(hash(hash(first, empty_leaf), e2), e1);
+        assert_eq!(ConfidentialTransactions::merkle_root(), root);
+
+        let root = hash(hash(hash(first, second), e2), e1);
+        assert_eq!(ConfidentialTransactions::simulate_insert(second).unwrap(), root);
+        assert_ok!(deposit(1, second));
+        assert_eq!(ConfidentialTransactions::merkle_root(), root);
+
+        // The empty leaf is reserved; the zero hash is an ordinary commitment under this
+        // convention.
+        assert_noop!(deposit(2, empty_leaf), Error::<Test>::InvalidCommitment);
+        assert_ok!(deposit(2, H256::zero()));
+    });
+}
+
+#[test]
+fn genesis_starts_from_the_empty_tree_root_of_the_configured_empty_leaf() {
+    let empty_leaf = H256::repeat_byte(0xee);
+    TreeDepth::set(3);
+    EmptyLeaf::set(Some(empty_leaf));
+
+    new_test_ext().execute_with(|| {
+        let empty_nodes = Pallet::<Test>::empty_nodes().unwrap();
+        assert_eq!(crate::EmptyNodes::<Test>::get(), Some(empty_nodes.clone()));
+        assert_eq!(ConfidentialTransactions::merkle_root(), empty_nodes[0]);
+        assert!(ConfidentialTransactions::is_known_root(empty_nodes[0]));
+    });
+}
+
+#[test]
+fn v3_migration_sets_the_empty_tree_root_only_while_the_tree_is_empty() {
+    use crate::migrations::v3::InnerMigrateV2ToV3;
+    use frame_support::traits::UncheckedOnRuntimeUpgrade;
+
+    let empty_leaf = H256::repeat_byte(0xee);
+    TreeDepth::set(3);
+    EmptyLeaf::set(Some(empty_leaf));
+
+    new_test_ext().execute_with(|| {
+        let empty_root = Pallet::<Test>::empty_nodes().unwrap()[0];
+        // State as left by v2: zero root, nothing cached.
+        crate::MerkleRoot::<Test>::kill();
+        crate::EmptyNodes::<Test>::kill();
+
+        InnerMigrateV2ToV3::<Test>::on_runtime_upgrade();
+        assert_eq!(ConfidentialTransactions::merkle_root(), empty_root);
+        assert!(crate::EmptyNodes::<Test>::get().is_some());
+
+        // A tree with leaves keeps its root.
+        assert_ok!(ConfidentialTransactions::deposit(
+            RuntimeOrigin::signed(1),
+            DEPOSIT_PROOF.get().unwrap().clone(),
+            vec![10u128.to_be_bytes().to_vec(), H256::from_low_u64_be(7).as_bytes().to_vec()],
+            10,
+        ));
+        let root = ConfidentialTransactions::merkle_root();
+        crate::EmptyNodes::<Test>::kill();
+        InnerMigrateV2ToV3::<Test>::on_runtime_upgrade();
+        assert_eq!(ConfidentialTransactions::merkle_root(), root);
+    });
+}
+
+#[test]
+fn compressed_proofs_verify_when_enabled() {
+    use ark_bn254::Bn254;
+    use ark_groth16::{Proof, VerifyingKey};
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
+    fn compress<A: CanonicalSerialize + CanonicalDeserialize>(uncompressed: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        A::deserialize_uncompressed(uncompressed)
+            .unwrap()
+            .serialize_compressed(&mut bytes)
+            .unwrap();
+        bytes
+    }
+
+    new_test_ext().execute_with(|| {
+        let inputs: Vec<Vec<u8>> =
+            (1..=5).map(|i| H256::from_low_u64_be(i).as_bytes().to_vec()).collect();
+        let (vk, proof) = prove_tagged(Circuit::Transfer, &inputs);
+        let compressed_proof = compress::<Proof<Bn254>>(&proof);
+        assert_eq!(compressed_proof.len() * 2, proof.len());
+        let dry_run = |proof: &Vec<u8>| {
+            ConfidentialTransactions::dry_run_verify(
+                RuntimeOrigin::signed(1),
+                Circuit::Transfer,
+                proof.clone(),
+                inputs.clone(),
+            )
+        };
+
+        CompressedProofs::set(true);
+        assert_ok!(ConfidentialTransactions::set_transfer_verification_key(
+            RuntimeOrigin::root(),
+            compress::<VerifyingKey<Bn254>>(&vk)
+        ));
+        assert!(PreparedVerificationKeys::<Test>::contains_key(Circuit::Transfer));
+        assert_ok!(dry_run(&compressed_proof));
+        assert!(dry_run(&proof).is_err());
+
+        // Without the cache the compressed key is still read in the configured encoding.
+        PreparedVerificationKeys::<Test>::remove(Circuit::Transfer);
+        assert_ok!(dry_run(&compressed_proof));
+    });
+}
+
+#[test]
+fn nullifier_spent_is_emitted_once_per_nullifier() {
+    new_test_ext().execute_with(|| {
+        let spent = || {
+            System::events()
+                .into_iter()
+                .filter_map(|record| match record.event {
+                    RuntimeEvent::ConfidentialTransactions(crate::Event::NullifierSpent {
+                        nullifier,
+                        at,
+                    }) => Some((nullifier, at)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        for (amount, commitment) in [(100u128, 1u64), (50, 2)] {
+            assert_ok!(ConfidentialTransactions::deposit(
+                RuntimeOrigin::signed(1),
+                DEPOSIT_PROOF.get().unwrap().clone(),
+                vec![
+                    amount.to_be_bytes().to_vec(),
+                    H256::from_low_u64_be(commitment).as_bytes().to_vec()
+                ],
+                amount
+            ));
+        }
+        assert!(spent().is_empty());
+
+        System::set_block_number(3);
+        let withdrawn = H256::from_low_u64_be(301);
+        assert_ok!(ConfidentialTransactions::withdraw(
+            RuntimeOrigin::signed(1),
+            TRANSFER_PROOF.get().unwrap().clone(),
+            vec![
+                ConfidentialTransactions::merkle_root().as_bytes().to_vec(),
+                withdrawn.as_bytes().to_vec(),
+                H256::from_low_u64_be(2).as_bytes().to_vec(),
+                50u128.to_be_bytes().to_vec(),
+                0u64.to_be_bytes().to_vec(),
+            ],
+            2,
+            50
+        ));
+        assert_eq!(spent(), vec![(withdrawn, 3)]);
+
+        System::set_block_number(4);
+        let (first, second) = (H256::from_low_u64_be(302), H256::from_low_u64_be(303));
+        assert_ok!(ConfidentialTransactions::transact(
+            RuntimeOrigin::signed(1),
+            TRANSFER_PROOF.get().unwrap().clone(),
+            vec![
+                ConfidentialTransactions::merkle_root().as_bytes().to_vec(),
+                first.as_bytes().to_vec(),
+                second.as_bytes().to_vec(),
+                H256::from_low_u64_be(401).as_bytes().to_vec(),
+                H256::from_low_u64_be(402).as_bytes().to_vec(),
+            ]
+        ));
+        assert_eq!(spent(), vec![(withdrawn, 3), (first, 4), (second, 4)]);
+    });
+}
@@ -26,8 +26,9 @@
 // Local module imports
 use super::{
     AccountId, AuthorityDiscovery, BABE_GENESIS_EPOCH_CONFIG, Babe, Balance, Block, BlockNumber,
-    Executive, Grandpa, Historical, InherentDataExt, Mmr, Nonce, Runtime, RuntimeCall,
-    RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, VERSION,
+    ConfidentialTransactions, EthereumBridge, Executive, Grandpa, Historical, InherentDataExt,
+    LaunchClaim, Mmr, Nonce, Runtime, RuntimeCall, RuntimeGenesisConfig, SessionKeys, System,
+    TransactionPayment, VERSION,
 };
 // External crates imports
 use crate::configs::{EpochDuration, mmr};
@@ -39,7 +40,7 @@ use frame_support::{
 };
 use pallet_grandpa::AuthorityId as GrandpaId;
 use sp_api::impl_runtime_apis;
-use sp_core::{OpaqueMetadata, crypto::KeyTypeId};
+use sp_core::{H160, H256, OpaqueMetadata, crypto::KeyTypeId};
 use sp_runtime::{
     ApplyExtrinsicResult,
     traits::{Block as BlockT, NumberFor},
@@ -316,6 +317,75 @@ impl_runtime_apis! {
         }
     }
 
+    impl pallet_private_transactions_runtime_api::ConfidentialTransactionsApi<Block, AccountId, Balance> for Runtime {
+        fn simulate_insert(leaf: H256) -> Option<H256> {
+            ConfidentialTransactions::simulate_insert(leaf).ok()
+        }
+
+        fn deposits_of(who: AccountId) -> Vec<u64> {
+            ConfidentialTransactions::deposits_of(&who)
+        }
+
+        fn is_known_root(root: H256) -> bool {
+            ConfidentialTransactions::is_known_root(root)
+        }
+
+        fn pool_balance() -> Balance {
+            ConfidentialTransactions::pool_balance()
+        }
+
+        fn tree_info() -> pallet_private_transactions_runtime_api::TreeInfo {
+            ConfidentialTransactions::tree_info()
+        }
+    }
+
+    impl pallet_bridge_runtime_api::BridgeApi<Block, AccountId, Balance, BlockNumber> for Runtime {
+        fn compute_message_id(
+            sender: AccountId,
+            amount: Balance,
+            eth_recipient: H160,
+            nonce: u64,
+        ) -> Option<[u8; 32]> {
+            EthereumBridge::compute_message_id(&sender, amount, eth_recipient, nonce).ok()
+        }
+
+        fn relayer_stats() -> Vec<(H160, u64)> {
+            EthereumBridge::relayer_stats()
+        }
+
+        fn can_release(
+            message_id: [u8; 32],
+            amount: Balance,
+            valid_until: BlockNumber,
+        ) -> pallet_bridge_runtime_api::ReleaseCheck {
+            EthereumBridge::can_release(message_id, amount, valid_until)
+        }
+
+        fn verify_release_signatures(
+            message_id: [u8; 32],
+            valid_until: BlockNumber,
+            signatures: Vec<Vec<u8>>,
+        ) -> u32 {
+            EthereumBridge::verify_release_signatures(message_id, valid_until, signatures)
+        }
+    }
+
+    impl pallet_launch_claim_runtime_api::LaunchClaimApi<Block, AccountId, Balance, BlockNumber>
+        for Runtime
+    {
+        fn claim_progress(who: AccountId) -> Option<(Balance, Balance, Balance)> {
+            LaunchClaim::claim_progress(&who)
+        }
+
+        fn claim_log(who: AccountId) -> Vec<(BlockNumber, Balance)> {
+            LaunchClaim::claim_log(&who).into_inner()
+        }
+
+        fn next_unlock(who: AccountId) -> Option<BlockNumber> {
+            LaunchClaim::next_unlock(&who)
+        }
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     impl frame_benchmarking::Benchmark<Block> for Runtime {
         fn benchmark_metadata(extra: bool) -> (
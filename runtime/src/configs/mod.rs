@@ -651,6 +651,36 @@ parameter_types! {
     /// The depth of the Merkle tree used for storing commitments.
     /// A depth of 32 allows for 2^32 (over 4 billion) leaves.
     pub const TreeDepth: u32 = 32;
+
+    /// How long a past Merkle root stays valid for withdrawals and transfers.
+    pub const MaxRootAge: BlockNumber = 1 * HOURS;
+
+    /// How many recent Merkle roots are retained for proofs against a slightly stale tree.
+    pub const RootHistorySize: u32 = 100;
+
+    /// How many deposit leaf indices are indexed per depositor.
+    pub const MaxDepositsPerAccount: u32 = 1024;
+
+    /// Smallest accepted deposit, to keep dust notes out of the commitment tree.
+    pub const MinShieldedDeposit: Balance = 1 * CENTS;
+
+    /// Most public inputs a proof may carry; the transfer circuit uses five.
+    pub const MaxPublicInputs: u32 = 8;
+
+    /// Skips zk-SNARK verification. Only enabled by the `dev-skip-proof-verification` feature,
+    /// which cannot be combined with `on-chain-release-build`.
+    pub const DevSkipProofVerification: bool = cfg!(feature = "dev-skip-proof-verification");
+
+    /// Whether proofs are bound to their circuit id. Off until the genesis verification keys are
+    /// regenerated from circuits that expose the id as their first public input.
+    pub const ProofDomainSeparation: bool = false;
+
+    /// Empty-tree convention of the commitment tree. `None` keeps every absent node at the zero
+    /// hash, which the current circuits and existing roots were built against.
+    pub const CommitmentTreeEmptyLeaf: Option<sp_core::H256> = None;
+
+    /// Proofs and verification keys use the uncompressed encoding of the genesis keys.
+    pub const CompressedProofs: bool = false;
 }
 
 impl pallet_private_transactions::Config for Runtime {
@@ -665,12 +695,51 @@ impl pallet_private_transactions::Config for Runtime {
 
     /// The depth of the Merkle tree.
     type TreeDepth = TreeDepth;
+
+    /// How many blocks a past Merkle root remains acceptable.
+    type MaxRootAge = MaxRootAge;
+
+    /// How many recent Merkle roots are retained.
+    type RootHistorySize = RootHistorySize;
+
+    /// Bound on the per-depositor leaf index.
+    type MaxDepositsPerAccount = MaxDepositsPerAccount;
+
+    /// Minimum amount per deposit.
+    type MinDeposit = MinShieldedDeposit;
+
+    /// Upper bound on public inputs per call.
+    type MaxPublicInputs = MaxPublicInputs;
+
+    /// Proof verification bypass for local dev chains.
+    type DevSkipProofVerification = DevSkipProofVerification;
+
+    /// Circuit id binding for deposit and transfer proofs.
+    type ProofDomainSeparation = ProofDomainSeparation;
+
+    /// Value of absent leaves in the commitment tree.
+    type EmptyLeaf = CommitmentTreeEmptyLeaf;
+
+    /// Encoding of submitted proofs and verification keys.
+    type CompressedProofs = CompressedProofs;
 }
 
 parameter_types! {
     pub const BridgePalletId: PalletId = PalletId(*b"brdglock");
     pub const RelayerThreshold: u32 = 1; // require 1 signature for now
     pub const MaxSignatures: u32 = 10;   // max 10 signatures per release
+    pub const BridgeSignatureScheme: pallet_bridge::SignaturePrefixScheme =
+        pallet_bridge::SignaturePrefixScheme::PersonalSign;
+    pub const BridgeMaxMemoLen: u32 = 64;
+    /// Relayer signatures expire at most a week after submission, after which processed ids can
+    /// be pruned without reopening them to replay.
+    pub const BridgeProcessedRetention: BlockNumber = 7 * DAYS;
+    pub const BridgeMaxReleaseBatch: u32 = 20;
+    /// No per-account cooldown between locks.
+    pub const BridgeLockCooldown: BlockNumber = 0;
+    pub const BridgeMaxActiveLocksPerAccount: u32 = 64;
+    /// Releases above 1M XOR have to be split into separately signed messages.
+    pub const BridgeMaxReleaseAmount: Balance = 1_000_000 * XOR;
 }
 
 impl pallet_bridge::Config for Runtime {
@@ -679,6 +748,19 @@ impl pallet_bridge::Config for Runtime {
     type BridgePalletId = BridgePalletId;
     type RelayerThreshold = RelayerThreshold;
     type MaxSignatures = MaxSignatures;
+    type SignaturePrefixScheme = BridgeSignatureScheme;
+    type MaxMemoLen = BridgeMaxMemoLen;
+    type ProcessedRetention = BridgeProcessedRetention;
+    type MaxReleaseBatch = BridgeMaxReleaseBatch;
+    type LockCooldown = BridgeLockCooldown;
+    type MaxActiveLocksPerAccount = BridgeMaxActiveLocksPerAccount;
+    type MaxReleaseAmount = BridgeMaxReleaseAmount;
+    type AdminOrigin = EnsureRoot<AccountId>;
+    /// Root, or two thirds of the technical committee, can pause the bridge.
+    type EmergencyOrigin = EitherOfDiverse<
+        EnsureRoot<AccountId>,
+        pallet_collective::EnsureProportionAtLeast<AccountId, TechnicalCollective, 2, 3>,
+    >;
 }
 
 impl pallet_insecure_randomness_collective_flip::Config for Runtime {}
@@ -1058,6 +1140,7 @@ parameter_types! {
     pub const DepositFactor: Balance = deposit(0, 32);
 
     pub const VestingPeriod: BlockNumber = 6*30 * DAYS;
+    pub const VestingCliff: BlockNumber = 0;
 }
 
 impl pallet_multisig::Config for Runtime {
@@ -1071,8 +1154,61 @@ impl pallet_multisig::Config for Runtime {
     type BlockNumberProvider = frame_system::Pallet<Runtime>;
 }
 
+parameter_types! {
+    pub const LaunchClaimFee: Balance = 0;
+    /// The owner can suspend an active campaign and resume it later.
+    pub const LaunchClaimAllowDeactivation: bool = true;
+    pub const LaunchClaimMaxClaimLogLen: u32 = 32;
+}
+
 impl pallet_launch_claim::Config for Runtime {
     type RuntimeEvent = RuntimeEvent;
     type Currency = Balances;
     type VestingPeriod = VestingPeriod;
+    type VestingCliff = VestingCliff;
+    type ClaimFee = LaunchClaimFee;
+    type ClaimFeeDestination = ResolveTo<TreasuryAccount, Balances>;
+    type AllowDeactivation = LaunchClaimAllowDeactivation;
+    type MaxClaimLogLen = LaunchClaimMaxClaimLogLen;
+}
+
+#[cfg(feature = "dev-faucet")]
+impl pallet_dev_faucet::Config for Runtime {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_runtime::traits::AccountIdConversion;
+    use std::collections::BTreeSet;
+
+    /// Whether every id derives its own sovereign account.
+    fn distinct_sovereign_accounts(ids: &[PalletId]) -> bool {
+        let accounts: BTreeSet<AccountId> =
+            ids.iter().map(|id| id.into_account_truncating()).collect();
+        accounts.len() == ids.len()
+    }
+
+    #[test]
+    fn pallet_ids_derive_distinct_sovereign_accounts() {
+        assert!(distinct_sovereign_accounts(&[
+            AirdropPalletId::get(),
+            ConfidentialTransactionsPalletId::get(),
+            BridgePalletId::get(),
+            TreasuryPalletId::get(),
+            PoolsPalletId::get(),
+            DelegatedStakingPalletId::get(),
+        ]));
+    }
+
+    #[test]
+    fn colliding_pallet_ids_are_detected() {
+        assert!(!distinct_sovereign_accounts(&[
+            AirdropPalletId::get(),
+            TreasuryPalletId::get(),
+            AirdropPalletId::get(),
+        ]));
+    }
 }
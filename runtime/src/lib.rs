@@ -3,6 +3,10 @@
 #![recursion_limit = "1024"]
 
 extern crate alloc;
+#[cfg(all(feature = "dev-skip-proof-verification", feature = "on-chain-release-build"))]
+compile_error!("`dev-skip-proof-verification` must never be enabled in an on-chain release build");
+#[cfg(all(feature = "dev-faucet", feature = "on-chain-release-build"))]
+compile_error!("`dev-faucet` must never be enabled in an on-chain release build");
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
@@ -75,10 +79,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     //   runtime in substitute for the on-chain Wasm runtime unless all of `spec_name`,
     //   `spec_version`, and `authoring_version` are the same between Wasm and native.
     // This value is set to 105 - upgrade from previous 104
-    spec_version: 1,
+    spec_version: 2,
     impl_version: 1,
     apis: apis::RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 2,
     system_version: 1,
 };
 // 1 in 4 blocks (on average, not counting collisions) will be primary babe blocks.
@@ -209,7 +213,15 @@ pub type SignedPayload = generic::SignedPayload<RuntimeCall, TxExtension>;
 ///
 /// This can be a tuple of types, each implementing `OnRuntimeUpgrade`.
 #[allow(unused_parens)]
-type Migrations = ();
+type Migrations = (
+    pallet_bridge::migrations::v2::MigrateV1ToV2<Runtime>,
+    pallet_bridge::migrations::v3::MigrateV2ToV3<Runtime>,
+    pallet_bridge::migrations::v4::MigrateV3ToV4<Runtime>,
+    pallet_bridge::migrations::v5::MigrateV4ToV5<Runtime>,
+    pallet_bridge::migrations::v6::MigrateV5ToV6<Runtime>,
+    pallet_private_transactions::migrations::v2::MigrateV1ToV2<Runtime>,
+    pallet_private_transactions::migrations::v3::MigrateV2ToV3<Runtime>,
+);
 
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
@@ -339,4 +351,8 @@ mod runtime {
     pub type LaunchClaim = pallet_launch_claim;
     #[runtime::pallet_index(35)]
     pub type RandomnessCollectiveFlip = pallet_insecure_randomness_collective_flip;
+
+    #[cfg(feature = "dev-faucet")]
+    #[runtime::pallet_index(36)]
+    pub type DevFaucet = pallet_dev_faucet;
 }